@@ -3,7 +3,7 @@ use std::str::FromStr;
 pub fn day_1_input() -> Vec<i64> {
     include_str!("../resources/day01part01.txt")
         .lines()
-        .map(|s| i64::from_str(&s).unwrap())
+        .map(|s| i64::from_str(s).unwrap())
         .collect()
 }
 
@@ -27,4 +27,44 @@ pub fn day_4_input() -> &'static str {
 
 pub fn day_5_input() -> &'static str {
     include_str!("../resources/day05part01.txt")
+}
+
+pub fn day_6_input() -> &'static str {
+    include_str!("../resources/day06part01.txt")
+}
+
+pub fn day_7_input() -> &'static str {
+    include_str!("../resources/day07part01.txt")
+}
+
+pub fn day_8_input() -> &'static str {
+    include_str!("../resources/day08part01.txt")
+}
+
+pub fn day_9_input() -> &'static str {
+    include_str!("../resources/day09part01.txt")
+}
+
+pub fn day_10_input() -> &'static str {
+    include_str!("../resources/day10part01.txt")
+}
+
+pub fn day_11_input() -> &'static str {
+    include_str!("../resources/day11part01.txt")
+}
+
+pub fn day_12_input() -> &'static str {
+    include_str!("../resources/day12part01.txt")
+}
+
+pub fn day_13_input() -> &'static str {
+    include_str!("../resources/day13part01.txt")
+}
+
+pub fn day_14_input() -> &'static str {
+    include_str!("../resources/day14part01.txt")
+}
+
+pub fn day_15_input() -> &'static str {
+    include_str!("../resources/day15part01.txt")
 }
\ No newline at end of file
@@ -0,0 +1,108 @@
+use std::hash::Hash;
+use std::collections::HashMap;
+
+/// A sequence of states that eventually repeats, possibly moved along by a fixed shift each time
+/// it comes round.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<S> {
+    /// The first step that's part of the cycle.
+    pub start: usize,
+    /// The number of steps before a state comes round again.
+    pub length: usize,
+    /// How far the state has moved each time it comes round.
+    pub shift: i64,
+    states: Vec<S>,
+}
+
+impl<S> Cycle<S> {
+    /// The state after `n` steps, as a state seen before the cycle came round plus how far it has
+    /// moved since.
+    pub fn at(&self, n: u64) -> (&S, i64) {
+        let start = self.start as u64;
+
+        if n < start {
+            return (&self.states[n as usize], 0);
+        }
+
+        let laps = (n - start) / self.length as u64;
+        let idx = start + (n - start) % self.length as u64;
+
+        (&self.states[idx as usize], self.shift * laps as i64)
+    }
+}
+
+/// Steps through states from `start` until one repeats an earlier state's shape.
+///
+/// `normalize` splits a state into its shape and its position, and states count as the same
+/// if their shapes are equal, whatever their positions. With a constant position this finds a
+/// plain cycle. Gives up with `None` after `max_steps` steps.
+pub fn find<S, F, N, K>(start: S, mut step: F, mut normalize: N, max_steps: usize) -> Option<Cycle<S>>
+    where F: FnMut(&S) -> S, N: FnMut(&S) -> (K, i64), K: Eq + Hash {
+
+    let mut seen: HashMap<K, (usize, i64)> = HashMap::new();
+    let mut states: Vec<S> = vec![start];
+
+    for n in 0..=max_steps {
+        let (shape, position) = normalize(&states[n]);
+
+        if let Some(&(first, first_position)) = seen.get(&shape) {
+            states.truncate(n);
+
+            return Some(Cycle {
+                start: first,
+                length: n - first,
+                shift: position - first_position,
+                states,
+            });
+        }
+
+        seen.insert(shape, (n, position));
+
+        let next = step(&states[n]);
+        states.push(next);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_cycle() {
+        // 3, 10, 5, 16, 8, 4, 2, 1, 4, 2, 1, ...
+        let collatz = |&n: &u64| if n % 2 == 0 { n / 2 } else { 3 * n + 1 };
+        let cycle = find(3, collatz, |&n| (n, 0), 100).unwrap();
+
+        assert_eq!((5, 3, 0), (cycle.start, cycle.length, cycle.shift));
+        assert_eq!((&16, 0), cycle.at(3));
+        assert_eq!((&2, 0), cycle.at(9));
+        assert_eq!((&4, 0), cycle.at(1_000_000_001));
+
+        assert_eq!(None, find(0_u64, |&n| n + 1, |&n| (n, 0), 100));
+    }
+
+    #[test]
+    fn test_shifted_cycle() {
+        // A pair that settles into moving 3 along every 2 steps once the gap closes
+        let step = |&(a, b): &(i64, i64)| match b - a {
+            0 => (a + 1, b + 2),
+            1 => (a + 2, b + 1),
+            _ => (a + 1, b - 1),
+        };
+        let cycle = find((0, 6), step, |&(a, b)| (b - a, a), 100).unwrap();
+
+        assert_eq!(2, cycle.length);
+        assert_eq!(3, cycle.shift);
+
+        let mut state = (0, 6);
+
+        for n in 0..50 {
+            let (cycled, shift) = cycle.at(n);
+            assert_eq!(state, (cycled.0 + shift, cycled.1 + shift));
+
+            state = step(&state);
+        }
+    }
+}
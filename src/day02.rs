@@ -46,12 +46,9 @@ impl<T> Solve<T> for Part2<T>
     fn solve(input: T) -> <Self as Solve<T>>::Output {
         let strings = input.as_ref();
 
-        for i in 0..(strings.len() - 1) {
-            let string1 = &strings[i];
-            for j in (i + 1)..strings.len() {
-                let string2 = &strings[j];
-
-                let common = common(&string1, &string2);
+        for (i, string1) in strings.iter().enumerate() {
+            for string2 in strings.iter().skip(i + 1) {
+                let common = common(string1, string2);
 
                 if common.len() == string1.len() - 1 {
                     return common;
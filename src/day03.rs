@@ -2,9 +2,9 @@ use super::*;
 
 use std::fmt;
 use std::str::FromStr;
-use std::iter;
 
-use regex::Regex;
+use grid::{Grid, Point};
+use parse;
 
 pub struct Part1<T>(::std::marker::PhantomData<T>);
 
@@ -14,23 +14,21 @@ impl<T> Solve<T> for Part1<T>
     type Output = Result<usize, Error>;
 
     fn solve(input: T) -> <Self as Solve<T>>::Output {
-        let claims: Vec<Claim> = input.as_ref().iter()
-            .map(|s| Claim::from_str(s))
-            .collect::<Result<Vec<Claim>, _>>()?;
+        let claims: Vec<Claim> = parse::lines(input.as_ref(), Claim::from_str)?;
 
         let max_size = claims.iter()
             .map(|c| {
                 (c.top + c.height).max(c.left + c.width)
             })
-            .max().ok_or("No claims")? as usize;
+            .max().ok_or("No claims")?;
 
-        let mut grid = Grid::square(max_size);
+        let mut fabric = Fabric::square(max_size);
 
         for claim in claims.iter() {
-            grid.add_claim(claim)?;
+            fabric.add_claim(claim)?;
         }
 
-        Ok(grid.overlapping())
+        Ok(fabric.overlapping())
     }
 }
 
@@ -42,24 +40,22 @@ impl <T> Solve<T> for Part2<T>
     type Output = Result<usize, Error>;
 
     fn solve(input: T) -> <Self as Solve<T>>::Output {
-        let claims: Vec<Claim> = input.as_ref().iter()
-            .map(|s| Claim::from_str(s))
-            .collect::<Result<Vec<Claim>, _>>()?;
+        let claims: Vec<Claim> = parse::lines(input.as_ref(), Claim::from_str)?;
 
         let max_size = claims.iter()
             .map(|c| {
                 (c.top + c.height).max(c.left + c.width)
             })
-            .max().ok_or("No claims")? as usize;
+            .max().ok_or("No claims")?;
 
-        let mut grid = Grid::square(max_size);
+        let mut fabric = Fabric::square(max_size);
 
         for claim in claims.iter() {
-            grid.add_claim(claim)?;
+            fabric.add_claim(claim)?;
         }
 
         for claim in claims.iter() {
-            if !grid.is_overlapping(claim)? {
+            if !fabric.is_overlapping(claim)? {
                 return Ok(claim.id);
             }
         }
@@ -87,23 +83,21 @@ impl Claim {
             height,
         }
     }
+
+    /// The square inches covered by this claim.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let (left, width) = (self.left, self.width);
+
+        (self.top..(self.top + self.height))
+            .flat_map(move |row| (left..(left + width)).map(move |col| Point::new(col as isize, row as isize)))
+    }
 }
 
 impl FromStr for Claim {
-    type Err = Box<::std::error::Error>;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
-        lazy_static! {
-            static ref RE: Regex = Regex::new(r"#(\d+?) @ (\d+?),(\d+?): (\d+?)x(\d+)").unwrap();
-        }
-
-        let caps = RE.captures(s).ok_or("Invalid regex")?;
-
-        let id = caps.get(1).ok_or("Invalid capture")?.as_str().parse::<usize>()?;
-        let left = caps.get(2).ok_or("Invalid capture")?.as_str().parse::<usize>()?;
-        let top = caps.get(3).ok_or("Invalid capture")?.as_str().parse::<usize>()?;
-        let width = caps.get(4).ok_or("Invalid capture")?.as_str().parse::<usize>()?;
-        let height = caps.get(5).ok_or("Invalid capture")?.as_str().parse::<usize>()?;
+        let (id, left, top, width, height) = parse::template("#{} @ {},{}: {}x{}", s)?;
 
         Ok(Self::new(id, left, top, width, height))
     }
@@ -126,66 +120,47 @@ impl fmt::Display for GridItem {
     }
 }
 
-pub struct Grid {
-    inner: Vec<Vec<GridItem>>,
+/// The sheet of fabric, tracking which claims cover each square inch.
+pub struct Fabric {
+    grid: Grid<GridItem>,
 }
 
-impl Grid {
+impl Fabric {
     pub fn square(size: usize) -> Self {
         Self::new(size, size)
     }
 
     pub fn new(rows: usize, cols: usize) -> Self {
-        let inner: Vec<Vec<GridItem>> = iter::repeat_with(|| vec![GridItem::Empty; cols])
-            .take(rows)
-            .collect();
-
         Self {
-            inner,
+            grid: Grid::new(cols, rows, GridItem::Empty),
         }
     }
 
-    pub fn get(&mut self, row: usize, col: usize) -> Option<&mut GridItem> {
-        let inner = match self.inner.get_mut(row) {
-            Some(inner) => inner,
-            None => return None,
-        };
-
-        inner.get_mut(col)
-    }
-
     pub fn add_claim(&mut self, claim: &Claim) -> Result<(), Error> {
-        for row in claim.top..(claim.top + claim.height) {
-            for col in claim.left..(claim.left + claim.width) {
+        for point in claim.points() {
+            let item = self.grid.get_mut(point).ok_or("Invalid row or col")?;
 
-                let item = self.get(row, col).ok_or("Invalid row or col")?;
-
-                *item = match item {
-                    GridItem::Empty => GridItem::Occupied(claim.id),
-                    GridItem::Occupied(_) | GridItem::Overlapping => GridItem::Overlapping,
-                };
-            }
+            *item = match item {
+                GridItem::Empty => GridItem::Occupied(claim.id),
+                GridItem::Occupied(_) | GridItem::Overlapping => GridItem::Overlapping,
+            };
         }
 
         Ok(())
     }
 
     pub fn overlapping(&self) -> usize {
-        self.inner.iter()
-            .flat_map(|inner| inner.iter())
-            .filter(|&item| *item == GridItem::Overlapping)
+        self.grid.iter()
+            .filter(|&(_point, item)| *item == GridItem::Overlapping)
             .count()
     }
 
-    pub fn is_overlapping(&mut self, claim: &Claim) -> Result<bool, Error> {
-        for row in claim.top..(claim.top + claim.height) {
-            for col in claim.left..(claim.left + claim.width) {
-
-                let item = self.get(row, col).ok_or("Invalid row or col")?;
+    pub fn is_overlapping(&self, claim: &Claim) -> Result<bool, Error> {
+        for point in claim.points() {
+            let item = self.grid.get(point).ok_or("Invalid row or col")?;
 
-                if *item == GridItem::Overlapping {
-                    return Ok(true);
-                }
+            if *item == GridItem::Overlapping {
+                return Ok(true);
             }
         }
 
@@ -193,17 +168,9 @@ impl Grid {
     }
 }
 
-impl fmt::Display for Grid {
+impl fmt::Display for Fabric {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        for inner in self.inner.iter() {
-            for item in inner {
-                write!(f, "{}", item);
-            }
-
-            write!(f, "\n");
-        }
-
-        Ok(())
+        write!(f, "{}", self.grid)
     }
 }
 
@@ -213,13 +180,13 @@ mod tests {
 
     #[test]
     fn test_parse_claim() {
-        let raw_claims = vec![
+        let raw_claims = [
             "#1 @ 1,3: 4x4".to_owned(),
             "#2 @ 3,1: 4x4".to_owned(),
             "#3 @ 5,5: 2x2".to_owned(),
         ];
 
-        let claims = vec![
+        let claims = [
             Claim::new(1, 1, 3, 4, 4),
             Claim::new(2, 3, 1, 4, 4),
             Claim::new(3, 5, 5, 2, 2),
@@ -1,11 +1,17 @@
 use super::*;
 
+use std::fmt;
+use std::iter;
+use std::vec;
 use std::str::FromStr;
-use std::collections::{HashMap};
+use std::cmp::Reverse;
+use std::ops::Range;
+use std::io::BufRead;
+use std::collections::{BTreeMap, BinaryHeap};
 
-use chrono::{NaiveDateTime, DateTime, Date, Utc};
-use regex::Regex;
-use itertools::{Itertools};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+
+use parse;
 
 pub struct Part1<T>(::std::marker::PhantomData<T>);
 
@@ -14,161 +20,637 @@ impl<T> Solve<T> for Part1<T>
     type Output = Result<u64, Error>;
 
     fn solve(input: T) -> <Self as Solve<T>>::Output {
-        let mut events: Vec<Event> = read_events(input.as_ref())?;
+        Ok(analyse(input.as_ref(), &MostAsleep)?.answer())
+    }
+}
 
-        // sort by guard id
-        events.sort_by_key(|e| e.guard_id.unwrap());
+pub struct Part2<T>(::std::marker::PhantomData<T>);
 
-        let mut asleep: HashMap<u64, [usize; 60]> = HashMap::new();
+impl<T> Solve<T> for Part2<T>
+    where T: AsRef<str> {
+    type Output = Result<u64, Error>;
 
-        for (key, val) in &events.into_iter()
-            .group_by(|event| event.guard_id.unwrap()) {
+    fn solve(input: T) -> <Self as Solve<T>>::Output {
+        Ok(analyse(input.as_ref(), &MostFrequentMinute)?.answer())
+    }
+}
+
+/// Reads a guard log and picks a guard and minute with the given strategy.
+pub fn analyse<S>(input: &str, strategy: &S) -> Result<Choice, Error>
+    where S: Strategy {
+
+    let events: Vec<Event> = read_events(input)?;
+
+    Ok(strategy.choose(&shifts(&events)).ok_or("Not enough events")?)
+}
 
-            let mut times = [0_usize; 60];
+/// The guard and minute picked by a `Strategy`, along with the score they were ranked by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Choice {
+    pub guard_id: u64,
+    pub minute: usize,
+    pub score: usize,
+}
 
-            let events: Vec<Event> = val.collect();
+impl Choice {
+    /// The puzzle answer: the guard id multiplied by the minute.
+    pub fn answer(&self) -> u64 {
+        self.guard_id * self.minute as u64
+    }
+}
 
-            let event_map: HashMap<DateTime<Utc>, EventType> = events.iter()
-                .map(|e| (e.time, e.event_type.clone()))
-                .collect();
+/// A rule for ranking guards by their sleep and picking the minute to sneak in on.
+pub trait Strategy {
+    fn choose(&self, shifts: &[Shift]) -> Option<Choice>;
+}
 
-            let mut dates: Vec<Date<Utc>> = events.iter()
-                .map(|e| e.time.date())
-                .dedup()
-                .collect();
-            dates.sort();
+/// Part 1: the guard with the most minutes asleep, at their most frequent minute.
+pub struct MostAsleep;
+
+impl Strategy for MostAsleep {
+    fn choose(&self, shifts: &[Shift]) -> Option<Choice> {
+        sleep_minutes(shifts).iter()
+            .map(|(&guard_id, times)| Choice {
+                guard_id,
+                minute: sleepiest_minute(times).0,
+                score: times.iter().sum(),
+            })
+            .max_by_key(|choice| choice.score)
+    }
+}
 
-            for date in dates.iter() {
-                let mut is_asleep = false;
+/// Part 2: the guard most frequently asleep on the same minute.
+pub struct MostFrequentMinute;
 
-                for time in 0_usize..60 {
-                    is_asleep = match event_map.get(&date.and_hms(0, time as u32, 0)) {
-                        Some(EventType::FallsAsleep) => true,
-                        Some(EventType::WakesUp) => false,
-                        Some(_) | None => is_asleep,
-                    };
+impl Strategy for MostFrequentMinute {
+    fn choose(&self, shifts: &[Shift]) -> Option<Choice> {
+        sleep_minutes(shifts).iter()
+            .map(|(&guard_id, times)| {
+                let (minute, score) = sleepiest_minute(times);
 
-                    if is_asleep {
-                        times[time] = times[time] + 1;
-                    }
+                Choice {
+                    guard_id,
+                    minute,
+                    score,
                 }
+            })
+            .max_by_key(|choice| choice.score)
+    }
+}
+
+/// The guard who took the longest single nap, scored by its length in minutes.
+pub struct LongestNap;
+
+impl Strategy for LongestNap {
+    fn choose(&self, shifts: &[Shift]) -> Option<Choice> {
+        let asleep = sleep_minutes(shifts);
+
+        shifts.iter()
+            .flat_map(|shift| shift.naps().iter()
+                .map(move |&(start, end)| (shift.guard_id(), (end - start).num_minutes() as usize)))
+            .max_by_key(|&(_guard_id, length)| length)
+            .map(|(guard_id, score)| Choice {
+                guard_id,
+                minute: sleepiest_minute(&asleep[&guard_id]).0,
+                score,
+            })
+    }
+}
+
+/// The guard who fell asleep on the most nights, scored by the number of nights.
+pub struct MostNightsAsleep;
+
+impl Strategy for MostNightsAsleep {
+    fn choose(&self, shifts: &[Shift]) -> Option<Choice> {
+        let mut nights: BTreeMap<u64, usize> = BTreeMap::new();
+
+        for shift in shifts.iter().filter(|shift| !shift.naps().is_empty()) {
+            *nights.entry(shift.guard_id()).or_insert(0) += 1;
+        }
+
+        let asleep = sleep_minutes(shifts);
+
+        nights.into_iter()
+            .max_by_key(|&(_guard_id, count)| count)
+            .map(|(guard_id, score)| Choice {
+                guard_id,
+                minute: sleepiest_minute(&asleep[&guard_id]).0,
+                score,
+            })
+    }
+}
+
+/// Applies another strategy to only the given minutes of the midnight hour.
+pub struct Windowed<S> {
+    pub minutes: Range<usize>,
+    pub strategy: S,
+}
+
+impl<S> Strategy for Windowed<S>
+    where S: Strategy {
+
+    fn choose(&self, shifts: &[Shift]) -> Option<Choice> {
+        let clipped: Vec<Shift> = shifts.iter()
+            .map(|shift| shift.clipped(self.minutes.clone()))
+            .collect();
+
+        self.strategy.choose(&clipped)
+    }
+}
+
+/// The minute a guard was most often asleep on, and how often, preferring the earliest minute.
+pub fn sleepiest_minute(times: &[usize; 60]) -> (usize, usize) {
+    times.iter()
+        .cloned()
+        .enumerate()
+        .max_by_key(|&(minute, count)| (count, Reverse(minute)))
+        .unwrap_or((0, 0))
+}
+
+/// Minutes of the midnight hour each guard spent asleep, summed over all of their shifts.
+pub fn sleep_minutes(shifts: &[Shift]) -> BTreeMap<u64, [usize; 60]> {
+    let mut asleep: BTreeMap<u64, [usize; 60]> = BTreeMap::new();
+
+    for shift in shifts {
+        let times = asleep.entry(shift.guard_id()).or_insert([0; 60]);
+
+        for (time, is_asleep) in shift.minutes_asleep().iter().enumerate() {
+            if *is_asleep {
+                times[time] += 1;
             }
+        }
+    }
+
+    asleep
+}
+
+/// Groups time-sorted events into one `Shift` per night.
+///
+/// Events that occur before the first shift begins are ignored, as is a nap that never ends.
+pub fn shifts(events: &[Event]) -> Vec<Shift> {
+    let mut shifts: Vec<Shift> = Vec::new();
+    let mut fell_asleep: Option<NaiveDateTime> = None;
+
+    for event in events {
+        let time = event.time;
 
-            asleep.insert(key, times);
+        match event.event_type {
+            EventType::BeginsShift(id) => {
+                shifts.push(Shift::new(id, time));
+                fell_asleep = None;
+            },
+            EventType::FallsAsleep => {
+                fell_asleep = fell_asleep.or(Some(time));
+            },
+            EventType::WakesUp => {
+                if let (Some(shift), Some(start)) = (shifts.last_mut(), fell_asleep.take()) {
+                    shift.naps.push((start, time));
+                }
+            },
         }
+    }
 
-        let (guard_id, _max_asleep) = asleep.iter()
-            .map(|(id, times)| (id, times.iter().sum::<usize>()))
-            .max_by_key(|(_id, count)| *count)
-            .ok_or("Not enough events")?;
+    shifts
+}
 
-        let max_minute = asleep.get(guard_id).unwrap()
-            .iter()
-            .map(|x| *x)
-            .enumerate()
-            .max_by_key(|i| i.1)
-            .unwrap_or_else(|| (0, 0)).0;
+/// Output format for the sleep charts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Csv,
+}
+
+/// Renders the night-by-night chart from the puzzle, one row per shift with `#` marking the
+/// minutes of the midnight hour the guard was asleep.
+pub fn night_chart(events: &[Event], format: Format) -> String {
+    let mut shifts = shifts(events);
+    shifts.sort_by_key(|shift| shift.night);
+
+    let mut out = String::new();
+
+    match format {
+        Format::Text => {
+            let id_width = shifts.iter()
+                .map(|shift| format!("#{}", shift.guard_id).len())
+                .max()
+                .unwrap_or(0)
+                .max(2);
+
+            out.push_str(&format!("{:<5}  {:<width$}  Minute\n", "Date", "ID", width = id_width));
+            out.push_str(&minute_header(5 + 2 + id_width + 2));
+
+            for shift in shifts.iter() {
+                let minutes: String = shift.minutes_asleep().iter()
+                    .map(|&asleep| if asleep { '#' } else { '.' })
+                    .collect();
+
+                out.push_str(&format!("{}  {:<width$}  {}\n",
+                    shift.night.format("%m-%d"), format!("#{}", shift.guard_id), minutes,
+                    width = id_width));
+            }
+        },
+        Format::Csv => {
+            out.push_str(&format!("date,guard,{}\n", minute_columns()));
+
+            for shift in shifts.iter() {
+                let minutes: Vec<&str> = shift.minutes_asleep().iter()
+                    .map(|&asleep| if asleep { "1" } else { "0" })
+                    .collect();
 
-        Ok(*guard_id * max_minute as u64)
+                out.push_str(&format!("{},{},{}\n", shift.night, shift.guard_id, minutes.join(",")));
+            }
+        },
     }
+
+    out
 }
 
-pub struct Part2<T>(::std::marker::PhantomData<T>);
+/// Renders how often each guard was asleep on every minute of the midnight hour.
+///
+/// The text format shades each minute relative to the guard's sleepiest minute, while the CSV
+/// format contains the raw counts.
+pub fn heatmap(events: &[Event], format: Format) -> String {
+    const SHADES: &[u8] = b".-:=+*#%@";
+
+    let asleep = sleep_minutes(&shifts(events));
+    let guard_ids: Vec<u64> = asleep.keys().cloned().collect();
+
+    let mut out = String::new();
+
+    match format {
+        Format::Text => {
+            let id_width = guard_ids.iter()
+                .map(|id| format!("#{}", id).len())
+                .max()
+                .unwrap_or(0)
+                .max(2);
+
+            out.push_str(&format!("{:<width$}  Minute\n", "ID", width = id_width));
+            out.push_str(&minute_header(id_width + 2));
+
+            for id in guard_ids.iter() {
+                let times = &asleep[id];
+                let max = times.iter().cloned().max().unwrap_or(0);
+
+                let minutes: String = times.iter()
+                    .map(|&count| match count {
+                        0 => SHADES[0],
+                        _ => SHADES[1 + count * (SHADES.len() - 2) / max],
+                    } as char)
+                    .collect();
+
+                out.push_str(&format!("{:<width$}  {}\n", format!("#{}", id), minutes, width = id_width));
+            }
+        },
+        Format::Csv => {
+            out.push_str(&format!("guard,{}\n", minute_columns()));
 
-impl<T> Solve<T> for Part2<T>
-    where T: AsRef<str> {
-    type Output = Result<u64, Error>;
+            for id in guard_ids.iter() {
+                let counts: Vec<String> = asleep[id].iter()
+                    .map(|count| count.to_string())
+                    .collect();
 
-    fn solve(input: T) -> <Self as Solve<T>>::Output {
-        let mut events: Vec<Event> = read_events(input.as_ref())?;
+                out.push_str(&format!("{},{}\n", id, counts.join(",")));
+            }
+        },
+    }
 
-        // sort by guard id
-        events.sort_by_key(|e| e.guard_id.unwrap());
+    out
+}
 
-        let mut asleep: HashMap<u64, [usize; 60]> = HashMap::new();
+/// The two header rows labelling minutes `00` to `59`, indented by `indent` spaces.
+fn minute_header(indent: usize) -> String {
+    let tens: String = (0..60).map(|m| (b'0' + m / 10) as char).collect();
+    let ones: String = (0..60).map(|m| (b'0' + m % 10) as char).collect();
 
-        for (key, val) in &events.into_iter()
-            .group_by(|event| event.guard_id.unwrap()) {
+    format!("{:indent$}{}\n{:indent$}{}\n", "", tens, "", ones, indent = indent)
+}
 
-            let mut times = [0_usize; 60];
+fn minute_columns() -> String {
+    (0..60).map(|m| m.to_string()).collect::<Vec<String>>().join(",")
+}
 
-            let events: Vec<Event> = val.collect();
+fn read_events(input: &str) -> Result<Vec<Event>, Error> {
+    read_events_with(input, Validation::Strict)
+}
+
+/// Parses and time-sorts a guard log, filling in the guard on duty for every event.
+///
+/// With `Validation::Strict` any inconsistency in the log is an error; with
+/// `Validation::Lenient` the events are repaired as described on `validate`.
+pub fn read_events_with(input: &str, validation: Validation) -> Result<Vec<Event>, Error> {
+    read_logs(iter::once(input.as_bytes()), validation)
+}
 
-            let event_map: HashMap<DateTime<Utc>, EventType> = events.iter()
-                .map(|e| (e.time, e.event_type.clone()))
-                .collect();
+/// Like `read_events_with`, but merges several logs as described on `merge_logs`.
+pub fn read_logs<I, R>(logs: I, validation: Validation) -> Result<Vec<Event>, Error>
+    where I: IntoIterator<Item = R>, R: BufRead {
 
-            let mut dates: Vec<Date<Utc>> = events.iter()
-                .map(|e| e.time.date())
-                .dedup()
-                .collect();
-            dates.sort();
+    let (events, inconsistencies) = validate(merge_logs(logs)?);
 
-            for date in dates.iter() {
-                let mut is_asleep = false;
+    if validation == Validation::Strict && !inconsistencies.is_empty() {
+        return Err(Box::new(InvalidLog(inconsistencies)));
+    }
 
-                for time in 0_usize..60 {
-                    is_asleep = match event_map.get(&date.and_hms(0, time as u32, 0)) {
-                        Some(EventType::FallsAsleep) => true,
-                        Some(EventType::WakesUp) => false,
-                        Some(_) | None => is_asleep,
-                    };
+    Ok(events)
+}
 
-                    if is_asleep {
-                        times[time] = times[time] + 1;
-                    }
-                }
+/// Reads unsorted guard logs, possibly overlapping one another, and merges them into a single
+/// stream of events in timestamp order.
+///
+/// Events logged identically more than once, whether in the same log or in different ones, are
+/// only yielded once. Blank lines are skipped.
+pub fn merge_logs<I, R>(logs: I) -> Result<MergedEvents, Error>
+    where I: IntoIterator<Item = R>, R: BufRead {
+
+    let mut sorted: Vec<Vec<Event>> = Vec::new();
+
+    for (log_idx, log) in logs.into_iter().enumerate() {
+        let mut events: Vec<Event> = Vec::new();
+
+        for (line_idx, line) in log.lines().enumerate() {
+            let line = line?;
+
+            if line.trim().is_empty() {
+                continue;
             }
 
-            asleep.insert(key, times);
+            let event = Event::from_str(&line)
+                .map_err(|e| format!("Log {}: {}", log_idx + 1, parse::with_line(e, line_idx + 1)))?;
+
+            events.push(event);
         }
 
-        let (guard_id, (max_idx, _max)) = asleep.iter()
-            .map(|(id, times)| (id, times.iter()
-                .enumerate()
-                .max_by_key(|(_i, x)| *x)
-                .unwrap_or((0_usize, &0_usize))))
-            .max_by_key(|(_id, (_max_idx, max))| *max)
-            .ok_or("Not enouch events")?;
+        events.sort_by_key(|e| e.key());
+        sorted.push(events);
+    }
+
+    Ok(MergedEvents::new(sorted))
+}
+
+/// Iterator over the events of several sorted logs, see `merge_logs`.
+pub struct MergedEvents {
+    logs: Vec<vec::IntoIter<Event>>,
+    heap: BinaryHeap<Reverse<((NaiveDateTime, EventType), usize)>>,
+    last: Option<Event>,
+}
+
+impl MergedEvents {
+    fn new(logs: Vec<Vec<Event>>) -> Self {
+        let logs: Vec<vec::IntoIter<Event>> = logs.into_iter()
+            .map(|events| events.into_iter())
+            .collect();
+
+        let heap = logs.iter()
+            .enumerate()
+            .filter_map(|(idx, events)| events.as_slice().first().map(|e| Reverse((e.key(), idx))))
+            .collect();
 
-        Ok(*guard_id * max_idx as u64)
+        Self {
+            logs,
+            heap,
+            last: None,
+        }
     }
 }
 
-fn read_events(input: &str) -> Result<Vec<Event>, Error> {
-    let mut events: Vec<Event> = input.lines()
-        .map(|line| Event::from_str(line))
-        .collect::<Result<_, _>>()?;
+impl Iterator for MergedEvents {
+    type Item = Event;
 
-    events.sort_by(|a, b| a.time.cmp(&b.time));
+    fn next(&mut self) -> Option<Event> {
+        while let Some(Reverse((_key, idx))) = self.heap.pop() {
+            let event = self.logs[idx].next()?;
 
-    // Fill down guard ids
-    let mut current_guard_id = match events[0].event_type {
-        EventType::BeginsShift(id) => id,
-        _ => return Err(From::from("Invalid set of events")),
-    };
+            if let Some(next) = self.logs[idx].as_slice().first() {
+                self.heap.push(Reverse((next.key(), idx)));
+            }
 
-    for event in events.iter_mut() {
-        event.guard_id = Some(match event.event_type {
-            EventType::BeginsShift(id) => {
-                current_guard_id = id;
-                id
+            // Duplicates sort next to each other, so only the last event needs checking
+            if self.last.as_ref() == Some(&event) {
+                continue;
+            }
+
+            self.last = Some(event.clone());
+
+            return Some(event);
+        }
+
+        None
+    }
+}
+
+/// Checks that time-sorted events form a consistent series of shifts and fills down guard ids.
+///
+/// Every inconsistency found is reported, and the returned events are repaired so that they can
+/// still be analysed:
+///
+/// * events before the first shift, wake-ups without a nap and repeated naps are skipped,
+/// * a guard still asleep when the next shift begins is woken up at the handover,
+/// * a nap still running at the end of the log is dropped.
+pub fn validate<I>(events: I) -> (Vec<Event>, Vec<Inconsistency>)
+    where I: IntoIterator<Item = Event> {
+
+    let mut events = events.into_iter().peekable();
+    let mut valid: Vec<Event> = Vec::new();
+    let mut inconsistencies: Vec<Inconsistency> = Vec::new();
+
+    if events.peek().is_none() {
+        inconsistencies.push(Inconsistency::EmptyLog);
+    }
+
+    let mut current_guard_id: Option<u64> = None;
+    let mut fell_asleep: Option<NaiveDateTime> = None;
+
+    for mut event in events {
+        let guard_id = match (&event.event_type, current_guard_id) {
+            (EventType::BeginsShift(id), _) => *id,
+            (_, Some(id)) => id,
+            (_, None) => {
+                inconsistencies.push(Inconsistency::NoShift(event.time));
+                continue;
             },
-            _ => current_guard_id,
-        });
+        };
+
+        match event.event_type {
+            EventType::BeginsShift(_) => {
+                if fell_asleep.take().is_some() {
+                    inconsistencies.push(Inconsistency::ShiftWhileAsleep(event.time));
+
+                    let mut wake = Event::new(event.time, EventType::WakesUp);
+                    wake.guard_id = current_guard_id;
+                    valid.push(wake);
+                }
+            },
+            EventType::FallsAsleep => {
+                if fell_asleep.is_some() {
+                    inconsistencies.push(Inconsistency::AlreadyAsleep(event.time));
+                    continue;
+                }
+
+                fell_asleep = Some(event.time);
+            },
+            EventType::WakesUp => {
+                if fell_asleep.take().is_none() {
+                    inconsistencies.push(Inconsistency::WakesWithoutSleep(event.time));
+                    continue;
+                }
+            },
+        }
+
+        current_guard_id = Some(guard_id);
+        event.guard_id = Some(guard_id);
+        valid.push(event);
     }
 
-    Ok(events)
+    if let Some(time) = fell_asleep {
+        inconsistencies.push(Inconsistency::EndsAsleep(time));
+
+        // Anything logged after the guard fell asleep was skipped, so the nap is the last event
+        valid.pop();
+    }
+
+    (valid, inconsistencies)
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Validation {
+    /// Reject logs containing any inconsistency.
+    Strict,
+    /// Skip or repair inconsistent events.
+    Lenient,
+}
+
+/// A problem found in a guard log, along with the time of the offending event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inconsistency {
+    EmptyLog,
+    NoShift(NaiveDateTime),
+    WakesWithoutSleep(NaiveDateTime),
+    AlreadyAsleep(NaiveDateTime),
+    ShiftWhileAsleep(NaiveDateTime),
+    EndsAsleep(NaiveDateTime),
+}
+
+impl fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        const FORMAT: &str = "%Y-%m-%d %H:%M";
+
+        match *self {
+            Inconsistency::EmptyLog =>
+                write!(f, "log is empty"),
+            Inconsistency::NoShift(time) =>
+                write!(f, "[{}] event before any guard begins shift", time.format(FORMAT)),
+            Inconsistency::WakesWithoutSleep(time) =>
+                write!(f, "[{}] wakes up without falling asleep", time.format(FORMAT)),
+            Inconsistency::AlreadyAsleep(time) =>
+                write!(f, "[{}] falls asleep while already asleep", time.format(FORMAT)),
+            Inconsistency::ShiftWhileAsleep(time) =>
+                write!(f, "[{}] begins shift while previous guard is asleep", time.format(FORMAT)),
+            Inconsistency::EndsAsleep(time) =>
+                write!(f, "[{}] falls asleep and never wakes up", time.format(FORMAT)),
+        }
+    }
+}
+
+/// Error returned by strict validation, listing every inconsistency in the log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidLog(pub Vec<Inconsistency>);
+
+impl fmt::Display for InvalidLog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "Invalid set of events")?;
+
+        for inconsistency in self.0.iter() {
+            write!(f, "\n  {}", inconsistency)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ::std::error::Error for InvalidLog {}
+
+/// A single guard's night on duty.
+///
+/// Shifts may begin shortly before midnight, so each one is attributed to the `night` whose
+/// midnight hour it covers rather than the date on which the guard arrived.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shift {
+    guard_id: u64,
+    night: NaiveDate,
+    /// Intervals the guard was asleep, from falling asleep (inclusive) to waking up (exclusive).
+    naps: Vec<(NaiveDateTime, NaiveDateTime)>,
+}
+
+impl Shift {
+    pub fn new(guard_id: u64, begins: NaiveDateTime) -> Self {
+        Self {
+            guard_id,
+            // Round to the nearest midnight
+            night: (begins + Duration::hours(12)).date(),
+            naps: Vec::new(),
+        }
+    }
+
+    pub fn guard_id(&self) -> u64 {
+        self.guard_id
+    }
+
+    pub fn night(&self) -> NaiveDate {
+        self.night
+    }
+
+    /// Intervals the guard was asleep, in the order they began.
+    pub fn naps(&self) -> &[(NaiveDateTime, NaiveDateTime)] {
+        &self.naps
+    }
+
+    /// A copy of this shift with its naps cut down to the given minutes of the midnight hour.
+    pub fn clipped(&self, minutes: Range<usize>) -> Self {
+        let from = self.minute(minutes.start);
+        let to = self.minute(minutes.end);
+
+        Self {
+            naps: self.naps.iter()
+                .map(|&(start, end)| (start.max(from), end.min(to)))
+                .filter(|&(start, end)| start < end)
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Which minutes of this night's midnight hour the guard was asleep for.
+    pub fn minutes_asleep(&self) -> [bool; 60] {
+        let mut minutes = [false; 60];
+
+        for (minute, is_asleep) in minutes.iter_mut().enumerate() {
+            let time = self.minute(minute);
+
+            *is_asleep = self.naps.iter()
+                .any(|&(start, end)| start <= time && time < end);
+        }
+
+        minutes
+    }
+
+    /// The given minute of this night's midnight hour.
+    fn minute(&self, minute: usize) -> NaiveDateTime {
+        NaiveDateTime::new(self.night, NaiveTime::from_hms_opt(0, 0, 0).unwrap())
+            + Duration::minutes(minute as i64)
+    }
+}
+
+/// A single line of a guard log.
+///
+/// Timestamps are kept as local time at the post, exactly as written in the log.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Event {
-    time: DateTime<Utc>,
+    time: NaiveDateTime,
     guard_id: Option<u64>,
     event_type: EventType,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum EventType {
     BeginsShift(u64),
     FallsAsleep,
@@ -176,32 +658,46 @@ pub enum EventType {
 }
 
 impl Event {
-    pub fn new(time: DateTime<Utc>, event_type: EventType) -> Self {
+    pub fn new(time: NaiveDateTime, event_type: EventType) -> Self {
         Self {
             time,
             guard_id: None,
             event_type,
         }
     }
+
+    pub fn time(&self) -> NaiveDateTime {
+        self.time
+    }
+
+    /// The guard on duty, once the event has been through `validate`.
+    pub fn guard_id(&self) -> Option<u64> {
+        self.guard_id
+    }
+
+    pub fn event_type(&self) -> &EventType {
+        &self.event_type
+    }
+
+    /// Orders events by time, and simultaneous events by type.
+    fn key(&self) -> (NaiveDateTime, EventType) {
+        (self.time, self.event_type.clone())
+    }
 }
 
 impl FromStr for Event {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
-        lazy_static! {
-            static ref RE: Regex = Regex::new(r"\[(.*?)\] (.*)").unwrap();
-        }
-
-        let caps = RE.captures(s).ok_or("Invalid line")?;
+        let fields = parse::fields("[{}] {}", s)?;
 
-        let raw_time = caps.get(1).ok_or("Invalid capture")?.as_str();
-        let raw_event = caps.get(2).ok_or("Invalid capture")?.as_str();
+        // Seconds are optional
+        let time = fields[0].parse_with(|raw_time| {
+            Ok(NaiveDateTime::parse_from_str(raw_time, "%Y-%m-%d %H:%M:%S")
+                .or_else(|_| NaiveDateTime::parse_from_str(raw_time, "%Y-%m-%d %H:%M"))?)
+        })?;
 
-        let time: DateTime<Utc> = DateTime::from_utc(
-            NaiveDateTime::parse_from_str(raw_time, "%Y-%m-%d %H:%M")?, Utc);
-
-        let event_type = EventType::from_str(raw_event)?;
+        let event_type = fields[1].parse_with(EventType::from_str)?;
 
         Ok(Event::new(time, event_type))
     }
@@ -217,13 +713,7 @@ impl FromStr for EventType {
             _ => (),
         };
 
-        lazy_static! {
-            static ref RE: Regex = Regex::new(r"Guard #(\d*?) begins shift").unwrap();
-        }
-
-        let caps = RE.captures(s).ok_or("Invalid line")?;
-
-        let guard_id = caps.get(1).ok_or("Invalid line")?.as_str().parse::<u64>()?;
+        let (guard_id,) = parse::template("Guard #{} begins shift", s)?;
 
         Ok(EventType::BeginsShift(guard_id))
     }
@@ -233,7 +723,28 @@ impl FromStr for EventType {
 mod tests {
     use super::*;
 
-    use chrono::{TimeZone};
+    const EXAMPLE: &str = "[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:05] falls asleep
+[1518-11-01 00:25] wakes up
+[1518-11-01 00:30] falls asleep
+[1518-11-01 00:55] wakes up
+[1518-11-01 23:58] Guard #99 begins shift
+[1518-11-02 00:40] falls asleep
+[1518-11-02 00:50] wakes up
+[1518-11-03 00:05] Guard #10 begins shift
+[1518-11-03 00:24] falls asleep
+[1518-11-03 00:29] wakes up
+[1518-11-04 00:02] Guard #99 begins shift
+[1518-11-04 00:36] falls asleep
+[1518-11-04 00:46] wakes up
+[1518-11-05 00:03] Guard #99 begins shift
+[1518-11-05 00:45] falls asleep
+[1518-11-05 00:55] wakes up";
+
+    fn time(month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(1518, month, day).unwrap()
+            .and_hms_opt(hour, minute, 0).unwrap()
+    }
 
     #[test]
     fn test_parse_event_type() {
@@ -244,13 +755,205 @@ mod tests {
 
     #[test]
     fn test_parse_event() {
-        let event1 = Event::new(Utc.ymd(1518, 11, 1).and_hms(0, 0, 0), EventType::BeginsShift(10));
-        let event2 = Event::new(Utc.ymd(1518, 11, 1).and_hms(0, 5, 0), EventType::FallsAsleep);
-        let event3 = Event::new(Utc.ymd(1518, 11, 1).and_hms(0, 25, 0), EventType::WakesUp);
+        let event1 = Event::new(time(11, 1, 0, 0), EventType::BeginsShift(10));
+        let event2 = Event::new(time(11, 1, 0, 5), EventType::FallsAsleep);
+        let event3 = Event::new(time(11, 1, 0, 25), EventType::WakesUp);
 
         assert_eq!(event1, Event::from_str("[1518-11-01 00:00] Guard #10 begins shift").unwrap());
         assert_eq!(event2, Event::from_str("[1518-11-01 00:05] falls asleep").unwrap());
         assert_eq!(event3, Event::from_str("[1518-11-01 00:25] wakes up").unwrap());
+
+        let event4 = Event::new(time(11, 1, 23, 59) + Duration::seconds(30), EventType::WakesUp);
+
+        assert_eq!(event4, Event::from_str("[1518-11-01 23:59:30] wakes up").unwrap());
+        assert!(Event::from_str("[1518-11-01 23:59:30+01:00] wakes up").is_err());
+        assert!(Event::from_str("[1518-02-29 00:00] wakes up").is_err());
+    }
+
+    #[test]
+    fn test_shift_before_midnight() {
+        let raw_input = "[1518-11-02 00:03] wakes up
+[1518-11-01 23:58] Guard #99 begins shift
+[1518-11-01 23:59] falls asleep
+[1518-11-02 00:40] falls asleep
+[1518-11-02 00:50] wakes up";
+
+        let shifts = shifts(&read_events(raw_input).unwrap());
+
+        assert_eq!(1, shifts.len());
+        assert_eq!(99, shifts[0].guard_id());
+        assert_eq!(NaiveDate::from_ymd_opt(1518, 11, 2).unwrap(), shifts[0].night());
+        assert_eq!(&[(time(11, 1, 23, 59), time(11, 2, 0, 3)), (time(11, 2, 0, 40), time(11, 2, 0, 50))],
+                   shifts[0].naps());
+
+        let minutes = shifts[0].minutes_asleep();
+
+        assert_eq!(13, minutes.iter().filter(|&&m| m).count());
+        assert!(minutes[0] && minutes[2] && !minutes[3]);
+        assert!(minutes[40] && minutes[49] && !minutes[50]);
+    }
+
+    #[test]
+    fn test_validate_strict() {
+        assert!(read_events("").is_err());
+
+        let raw_input = "[1518-11-01 00:02] falls asleep
+[1518-11-01 00:00] Guard #10 begins shift";
+
+        let err = read_events(raw_input).unwrap_err();
+
+        assert_eq!("Invalid set of events\n  [1518-11-01 00:02] falls asleep and never wakes up", err.to_string());
+    }
+
+    #[test]
+    fn test_validate_lenient() {
+        let raw_input = "[1518-10-31 00:10] wakes up
+[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:01] wakes up
+[1518-11-01 00:05] falls asleep
+[1518-11-01 00:06] falls asleep
+[1518-11-01 00:25] wakes up
+[1518-11-01 00:30] falls asleep
+[1518-11-01 23:58] Guard #99 begins shift
+[1518-11-02 00:40] falls asleep";
+
+        let mut events: Vec<Event> = raw_input.lines()
+            .map(|line| Event::from_str(line).unwrap())
+            .collect();
+        events.sort_by_key(|e| e.time);
+
+        let (events, inconsistencies) = validate(events);
+
+        assert_eq!(vec![
+            Inconsistency::NoShift(time(10, 31, 0, 10)),
+            Inconsistency::WakesWithoutSleep(time(11, 1, 0, 1)),
+            Inconsistency::AlreadyAsleep(time(11, 1, 0, 6)),
+            Inconsistency::ShiftWhileAsleep(time(11, 1, 23, 58)),
+            Inconsistency::EndsAsleep(time(11, 2, 0, 40)),
+        ], inconsistencies);
+
+        let repaired: Vec<(Option<u64>, EventType)> = events.into_iter()
+            .map(|e| (e.guard_id, e.event_type))
+            .collect();
+
+        assert_eq!(vec![
+            (Some(10), EventType::BeginsShift(10)),
+            (Some(10), EventType::FallsAsleep),
+            (Some(10), EventType::WakesUp),
+            (Some(10), EventType::FallsAsleep),
+            (Some(10), EventType::WakesUp),
+            (Some(99), EventType::BeginsShift(99)),
+        ], repaired);
+
+        assert!(read_events_with("", Validation::Lenient).unwrap().is_empty());
+        assert!(Part1::solve("").is_err());
+    }
+
+    #[test]
+    fn test_night_chart() {
+        let events = read_events(EXAMPLE).unwrap();
+
+        assert_eq!("Date   ID   Minute
+            000000000011111111112222222222333333333344444444445555555555
+            012345678901234567890123456789012345678901234567890123456789
+11-01  #10  .....####################.....#########################.....
+11-02  #99  ........................................##########..........
+11-03  #10  ........................#####...............................
+11-04  #99  ....................................##########..............
+11-05  #99  .............................................##########.....
+", night_chart(&events, Format::Text));
+
+        let csv = night_chart(&events, Format::Csv);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(6, lines.len());
+        assert!(lines[0].starts_with("date,guard,0,1,2,"));
+        assert!(lines[0].ends_with(",58,59"));
+        assert!(lines[2].starts_with("1518-11-02,99,0,0,"));
+        assert_eq!(10, lines[2].matches(",1").count());
+    }
+
+    #[test]
+    fn test_heatmap() {
+        let events = read_events(EXAMPLE).unwrap();
+
+        assert_eq!("ID   Minute
+     000000000011111111112222222222333333333344444444445555555555
+     012345678901234567890123456789012345678901234567890123456789
+#10  .....+++++++++++++++++++@++++.+++++++++++++++++++++++++.....
+#99  ....................................====*****@****=====.....
+", heatmap(&events, Format::Text));
+
+        let csv = heatmap(&events, Format::Csv);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(3, lines.len());
+        assert!(lines[0].starts_with("guard,0,1,2,"));
+        assert!(lines[2].starts_with("99,0,0,"));
+        assert!(lines[2].contains(",2,3,2,"));
+    }
+
+    #[test]
+    fn test_strategies() {
+        let choose = |strategy: &dyn Strategy| strategy.choose(&shifts(&read_events(EXAMPLE).unwrap())).unwrap();
+        let choice = |guard_id, minute, score| Choice { guard_id, minute, score };
+
+        assert_eq!(choice(10, 24, 50), choose(&MostAsleep));
+        assert_eq!(choice(99, 45, 3), choose(&MostFrequentMinute));
+        assert_eq!(choice(10, 24, 25), choose(&LongestNap));
+        assert_eq!(choice(99, 45, 3), choose(&MostNightsAsleep));
+        assert_eq!(choice(10, 24, 25), choose(&Windowed { minutes: 0..30, strategy: MostAsleep }));
+        assert_eq!(choice(99, 45, 26), choose(&Windowed { minutes: 40..60, strategy: MostAsleep }));
+        assert_eq!(choice(10, 5, 15), choose(&Windowed { minutes: 0..20, strategy: LongestNap }));
+
+        assert!(LongestNap.choose(&[]).is_none());
+    }
+
+    #[test]
+    fn test_merge_logs() {
+        let log1 = "[1518-11-04 00:46] wakes up
+[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-03 00:24] falls asleep
+[1518-11-01 00:25] wakes up
+[1518-11-01 00:05] falls asleep
+
+[1518-11-01 00:30] falls asleep
+[1518-11-01 00:55] wakes up
+[1518-11-01 23:58] Guard #99 begins shift
+[1518-11-02 00:40] falls asleep
+[1518-11-04 00:46] wakes up";
+
+        let log2 = "[1518-11-05 00:55] wakes up
+[1518-11-02 00:50] wakes up
+[1518-11-03 00:05] Guard #10 begins shift
+[1518-11-03 00:24] falls asleep
+[1518-11-03 00:29] wakes up
+[1518-11-04 00:02] Guard #99 begins shift
+[1518-11-04 00:36] falls asleep";
+
+        let log3 = "[1518-11-05 00:45] falls asleep
+[1518-11-05 00:03] Guard #99 begins shift
+[1518-11-01 00:00] Guard #10 begins shift";
+
+        let merged: Vec<Event> = merge_logs(vec![log1.as_bytes(), log2.as_bytes(), log3.as_bytes()])
+            .unwrap()
+            .collect();
+
+        let expected: Vec<Event> = merge_logs(iter::once(EXAMPLE.as_bytes())).unwrap().collect();
+
+        assert_eq!(17, merged.len());
+        assert_eq!(expected, merged);
+        assert!(merged.windows(2).all(|w| w[0].time() < w[1].time()));
+        assert_eq!(&EventType::BeginsShift(10), merged[0].event_type());
+        assert_eq!(None, merged[0].guard_id());
+        assert_eq!(Some(10), read_events(EXAMPLE).unwrap()[1].guard_id());
+
+        assert_eq!(read_events(EXAMPLE).unwrap(),
+                   read_logs(vec![log1.as_bytes(), log2.as_bytes(), log3.as_bytes()], Validation::Strict).unwrap());
+
+        let err = merge_logs(vec![log1.as_bytes(), "\n[1518-11-01 00:05] sneezes".as_bytes()]).err().unwrap();
+
+        assert_eq!("Log 2: Line 2, column 20: Expected `Guard #`", err.to_string());
     }
 
     #[test]
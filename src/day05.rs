@@ -1,13 +1,17 @@
 use super::*;
 
+use std::fmt;
+use std::hash::Hash;
+use std::collections::{HashMap, HashSet, LinkedList, VecDeque};
+
 pub struct Part1<T>(::std::marker::PhantomData<T>);
 
 impl<T> Solve<T> for Part1<T>
     where T: AsRef<str> {
-    type Output = usize;
+    type Output = Result<usize, Error>;
 
     fn solve(input: T) -> <Self as Solve<T>>::Output {
-        react_polymer(input.as_ref().chars()).len()
+        Ok(react_polymer(parse_polymer(input.as_ref())?).len())
     }
 }
 
@@ -15,60 +19,353 @@ pub struct Part2<T>(::std::marker::PhantomData<T>);
 
 impl<T> Solve<T> for Part2<T>
     where T: AsRef<str> {
-    type Output = usize;
+    type Output = Result<Removal, Error>;
 
     fn solve(input: T) -> <Self as Solve<T>>::Output {
-        let pairs: Vec<(char, char)> = "abcdefghijklmnopqrstuvwxyz".chars()
-            .map(|c| (c, c.to_ascii_uppercase()))
-            .collect();
+        // Removing a unit type can't stop a pair that reacted before from reacting, so every
+        // removal can start from the fully reacted polymer
+        let reduced = react_polymer(parse_polymer(input.as_ref())?);
+
+        Ok(best_removal(&reduced))
+    }
+}
+
+/// Trims surrounding whitespace from a polymer and checks that every unit is an ASCII letter.
+///
+/// The error names the first offending byte and its offset in `input`.
+pub fn parse_polymer(input: &str) -> Result<&[u8], Error> {
+    let polymer = input.trim();
+    let start = input.len() - input.trim_start().len();
+
+    match polymer.bytes().position(|unit| !unit.is_ascii_alphabetic()) {
+        Some(idx) => Err(From::from(format!("Invalid unit {:#04x} at byte {}",
+            polymer.as_bytes()[idx], start + idx))),
+        None => Ok(polymer.as_bytes()),
+    }
+}
+
+/// A unit type removed from a polymer, and the length of the polymer once it fully reacts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Removal {
+    pub unit: char,
+    pub length: usize,
+}
 
-        let chars: Vec<char> = input.as_ref().chars().collect();
+impl fmt::Display for Removal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{} (removed {}/{})", self.length, self.unit, self.unit.to_ascii_uppercase())
+    }
+}
+
+#[cfg(not(feature = "parallel"))]
+fn best_removal(polymer: &[u8]) -> Removal {
+    (b'a'..=b'z')
+        .map(|unit| remove_unit(polymer, unit))
+        .min_by_key(|removal| removal.length)
+        .expect("no unit types")
+}
+
+#[cfg(feature = "parallel")]
+fn best_removal(polymer: &[u8]) -> Removal {
+    use rayon::prelude::*;
 
-        pairs.iter()
-            .map(|(lower, upper)| {
-                chars.iter()
-                    .filter(|&c| c != lower)
-                    .filter(|&c| c != upper)
-                    .map(|c| *c)
-                    .collect::<Vec<char>>()
-            })
-            .map(|chars| react_polymer(chars).len())
-            .min()
-            .unwrap_or(0)
+    (b'a'..=b'z').into_par_iter()
+        .map(|unit| remove_unit(polymer, unit))
+        .min_by_key(|removal| removal.length)
+        .expect("no unit types")
+}
+
+fn remove_unit(polymer: &[u8], unit: u8) -> Removal {
+    let units: Vec<u8> = polymer.iter()
+        .filter(|u| !u.eq_ignore_ascii_case(&unit))
+        .cloned()
+        .collect();
+
+    Removal {
+        unit: unit as char,
+        length: react_polymer(&units).len(),
     }
 }
 
-fn react_polymer<T>(input: T) -> Vec<char>
-    where T: IntoIterator<Item = char> {
+/// Fully reacts a polymer of ASCII letters using the puzzle's rules.
+fn react_polymer(polymer: &[u8]) -> Vec<u8> {
+    react_with(polymer, &OppositeCase)
+}
 
-    let mut a: Vec<char> = input.into_iter().collect();
-    let mut b: Vec<char> = Vec::new();
+/// Fully reacts a polymer in a single pass, using `rule` to decide which units react.
+///
+/// Units are pushed onto a stack of the polymer reduced so far, and a unit that reacts with the
+/// top of the stack destroys it instead. Since the reduced prefix never contains a reacting pair,
+/// only the newest unit can ever react.
+pub fn react_with<T, R>(polymer: &[T], rule: &R) -> Vec<T>
+    where T: Clone, R: ReactionRule<T> + ?Sized {
 
-    loop {
-        let mut destroyed: usize = 0;
+    let mut reduced: Vec<T> = Vec::with_capacity(polymer.len());
 
-        let mut i: usize = 0;
-        while i < a.len() - 1 {
-            if (a[i] as isize - a[i + 1] as isize).abs() == 32 {
-                destroyed += 1;
-                i += 1;
+    for unit in polymer {
+        match reduced.last() {
+            Some(top) if rule.reacts(top, unit) => {
+                reduced.pop();
+            },
+            _ => reduced.push(unit.clone()),
+        }
+    }
+
+    reduced
+}
+
+/// A polymer that reacts as units arrive, for polymers that are read in chunks.
+///
+/// The reduced polymer is kept at all times, so it never contains a reacting pair. It's stored as
+/// a list of chunks so that two reducers can be joined without copying either of them.
+#[derive(Debug, Clone, Default)]
+pub struct Reducer<R = OppositeCase> {
+    /// Never contains an empty chunk.
+    chunks: LinkedList<VecDeque<u8>>,
+    len: usize,
+    rule: R,
+}
+
+impl Reducer {
+    pub fn new() -> Self {
+        Self::with_rule(OppositeCase)
+    }
+}
+
+impl<R> Reducer<R>
+    where R: ReactionRule<u8> {
+
+    pub fn with_rule(rule: R) -> Self {
+        Self {
+            chunks: LinkedList::new(),
+            len: 0,
+            rule,
+        }
+    }
+
+    /// Adds units to the end of the polymer, reacting them as they go.
+    pub fn push(&mut self, units: &[u8]) {
+        for unit in units {
+            if self.back().is_some_and(|back| self.rule.reacts(&back, unit)) {
+                self.pop_back();
             } else {
-                b.push(a[i]);
+                match self.chunks.back_mut() {
+                    Some(chunk) => chunk.push_back(*unit),
+                    None => self.chunks.push_back(VecDeque::from(vec![*unit])),
+                }
+
+                self.len += 1;
+            }
+        }
+    }
+
+    /// Appends another reduced polymer to this one in O(overlap) time.
+    ///
+    /// Both sides are already reduced, so reactions can only happen where they meet and only
+    /// the overlapping units are checked. What's left of the two chunk lists is then linked
+    /// together without copying any units.
+    pub fn concat(&mut self, mut other: Self) {
+        while let (Some(back), Some(front)) = (self.back(), other.front()) {
+            if !self.rule.reacts(&back, &front) {
+                break;
+            }
+
+            self.pop_back();
+            other.pop_front();
+        }
+
+        self.len += other.len;
+        self.chunks.append(&mut other.chunks);
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.chunks.iter().flatten().cloned().collect()
+    }
+
+    fn back(&self) -> Option<u8> {
+        self.chunks.back().and_then(|chunk| chunk.back()).cloned()
+    }
+
+    fn front(&self) -> Option<u8> {
+        self.chunks.front().and_then(|chunk| chunk.front()).cloned()
+    }
+
+    fn pop_back(&mut self) {
+        if let Some(chunk) = self.chunks.back_mut() {
+            chunk.pop_back();
+            self.len -= 1;
+
+            if chunk.is_empty() {
+                self.chunks.pop_back();
+            }
+        }
+    }
+
+    fn pop_front(&mut self) {
+        if let Some(chunk) = self.chunks.front_mut() {
+            chunk.pop_front();
+            self.len -= 1;
+
+            if chunk.is_empty() {
+                self.chunks.pop_front();
+            }
+        }
+    }
+}
+
+/// Steps through the reactions of a polymer in the order the single-pass reducer finds them.
+pub fn reactions<'a, T, R>(polymer: &'a [T], rule: &'a R) -> Reactions<'a, T, R>
+    where T: Clone, R: ReactionRule<T> + ?Sized {
+
+    Reactions {
+        reduced: Vec::with_capacity(polymer.len()),
+        rest: polymer,
+        rule,
+    }
+}
+
+/// A pair of units destroyed by a reaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reaction<T> {
+    /// Index of the pair's first unit in the polymer before the reaction.
+    pub position: usize,
+    pub pair: (T, T),
+    /// Length of the polymer after the reaction.
+    pub remaining: usize,
+}
+
+/// Iterator over the reactions of a polymer, see `reactions`.
+pub struct Reactions<'a, T, R>
+    where T: 'a, R: 'a + ?Sized {
+
+    reduced: Vec<T>,
+    rest: &'a [T],
+    rule: &'a R,
+}
+
+impl<'a, T, R> Reactions<'a, T, R>
+    where T: Clone, R: ReactionRule<T> + ?Sized {
+
+    /// The polymer as it stands after the reactions yielded so far.
+    pub fn polymer(&self) -> Vec<T> {
+        self.reduced.iter()
+            .chain(self.rest.iter())
+            .cloned()
+            .collect()
+    }
+}
+
+impl<'a, T, R> Iterator for Reactions<'a, T, R>
+    where T: Clone, R: ReactionRule<T> + ?Sized {
+
+    type Item = Reaction<T>;
+
+    fn next(&mut self) -> Option<Reaction<T>> {
+        while let Some((unit, rest)) = self.rest.split_first() {
+            self.rest = rest;
+
+            if self.reduced.last().is_some_and(|top| self.rule.reacts(top, unit)) {
+                let top = self.reduced.pop()?;
+
+                return Some(Reaction {
+                    position: self.reduced.len(),
+                    pair: (top, unit.clone()),
+                    remaining: self.reduced.len() + self.rest.len(),
+                });
             }
 
-            i += 1;
+            self.reduced.push(unit.clone());
         }
 
-        if i == a.len() - 1 {
-            b.push(a[i]);
+        None
+    }
+}
+
+/// Renders every intermediate polymer, one per line, like the worked example in the puzzle.
+pub fn trace(polymer: &[u8]) -> String {
+    let mut reactions = reactions(polymer, &OppositeCase);
+    let mut out = format!("{}\n", String::from_utf8_lossy(polymer));
+
+    while let Some(reaction) = reactions.next() {
+        out.push_str(&format!("{:width$}  removed {}{} at {}\n",
+            String::from_utf8_lossy(&reactions.polymer()),
+            reaction.pair.0 as char, reaction.pair.1 as char, reaction.position,
+            width = polymer.len()));
+    }
+
+    out
+}
+
+/// Decides whether two adjacent units annihilate each other.
+pub trait ReactionRule<T> {
+    fn reacts(&self, a: &T, b: &T) -> bool;
+}
+
+/// The puzzle's rule: the same ASCII letter in opposite cases reacts.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OppositeCase;
+
+impl ReactionRule<u8> for OppositeCase {
+    fn reacts(&self, a: &u8, b: &u8) -> bool {
+        a != b && a.is_ascii_alphabetic() && a.eq_ignore_ascii_case(b)
+    }
+}
+
+/// An arbitrary set of reacting pairs, in either order.
+#[derive(Debug, Clone)]
+pub struct Pairs<T>
+    where T: Eq + Hash {
+
+    pairs: HashMap<T, HashSet<T>>,
+}
+
+impl<T> Pairs<T>
+    where T: Eq + Hash + Clone {
+
+    pub fn new<I>(pairs: I) -> Self
+        where I: IntoIterator<Item = (T, T)> {
+
+        let mut map: HashMap<T, HashSet<T>> = HashMap::new();
+
+        for (a, b) in pairs {
+            map.entry(a.clone()).or_default().insert(b.clone());
+            map.entry(b).or_default().insert(a);
         }
 
-        if destroyed == 0 {
-            return b;
+        Self {
+            pairs: map,
         }
+    }
+}
+
+impl<T> ReactionRule<T> for Pairs<T>
+    where T: Eq + Hash {
 
-        std::mem::swap(&mut a, &mut b);
-        b.clear();
+    fn reacts(&self, a: &T, b: &T) -> bool {
+        self.pairs.get(a).is_some_and(|reacting| reacting.contains(b))
+    }
+}
+
+/// Any letter reacts with itself in the opposite case, using Unicode case mappings.
+///
+/// Letters match if either their lowercase or their uppercase forms are equal, so `ς` reacts with
+/// `Σ` as well as `σ` does.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UnicodeCase;
+
+impl ReactionRule<char> for UnicodeCase {
+    fn reacts(&self, a: &char, b: &char) -> bool {
+        let opposite = (a.is_lowercase() && b.is_uppercase()) || (a.is_uppercase() && b.is_lowercase());
+
+        opposite && (a.to_lowercase().eq(b.to_lowercase()) || a.to_uppercase().eq(b.to_uppercase()))
     }
 }
 
@@ -76,13 +373,140 @@ fn react_polymer<T>(input: T) -> Vec<char>
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_react_polymer() {
+        assert_eq!(b"", &react_polymer(b"")[..]);
+        assert_eq!(b"a", &react_polymer(b"a")[..]);
+        assert_eq!(b"", &react_polymer(b"aA")[..]);
+        assert_eq!(b"", &react_polymer(b"abBA")[..]);
+        assert_eq!(b"abAB", &react_polymer(b"abAB")[..]);
+        assert_eq!(b"aabAAB", &react_polymer(b"aabAAB")[..]);
+        assert_eq!(b"dabCBAcaDA", &react_polymer(b"dabAcCaCBAcCcaDA")[..]);
+    }
+
+    #[test]
+    fn test_reaction_rules() {
+        assert_eq!(b"@`", &react_polymer(b"@`")[..]);
+        assert_eq!(b"[{", &react_polymer(b"[{")[..]);
+
+        let pairs = Pairs::new(vec![('+', '-'), ('(', ')')]);
+        let polymer: Vec<char> = "a(+-)b-+-c".chars().collect();
+
+        assert_eq!(vec!['a', 'b', '-', 'c'], react_with(&polymer, &pairs));
+
+        let polymer: Vec<char> = "xΣσyÄäÉzςΣ".chars().collect();
+
+        assert_eq!(vec!['x', 'y', 'É', 'z'], react_with(&polymer, &UnicodeCase));
+        assert!(!UnicodeCase.reacts(&'σ', &'ς'));
+        assert!(!UnicodeCase.reacts(&'1', &'1'));
+    }
+
+    #[test]
+    fn test_parse_polymer() {
+        assert_eq!(b"dabAcC", parse_polymer("dabAcC\n").unwrap());
+        assert_eq!(b"dabAcC", parse_polymer(" \tdabAcC \r\n").unwrap());
+        assert_eq!(b"", parse_polymer("\n").unwrap());
+
+        assert_eq!("Invalid unit 0x20 at byte 5", parse_polymer("\ndabA cC\n").unwrap_err().to_string());
+        assert_eq!("Invalid unit 0xc3 at byte 3", parse_polymer("dab\u{c4}\n").unwrap_err().to_string());
+    }
+
+    #[test]
+    fn test_reducer() {
+        let mut reducer = Reducer::new();
+
+        assert!(reducer.is_empty());
+
+        reducer.push(b"dabAc");
+        assert_eq!(5, reducer.len());
+
+        reducer.push(b"C");
+        assert_eq!(4, reducer.len());
+
+        reducer.push(b"aCBAcC");
+        assert_eq!(b"dabCBA", &reducer.to_vec()[..]);
+
+        reducer.push(b"");
+        reducer.push(b"caDA");
+        assert_eq!(b"dabCBAcaDA", &reducer.to_vec()[..]);
+    }
+
+    #[test]
+    fn test_reducer_concat() {
+        let polymer = b"dabAcCaCBAcCcaDAxyzZYXAD";
+
+        for split in 0..=polymer.len() {
+            let mut left = Reducer::new();
+            let mut right = Reducer::new();
+
+            left.push(&polymer[..split]);
+            right.push(&polymer[split..]);
+            left.concat(right);
+
+            assert_eq!(react_polymer(polymer), left.to_vec());
+        }
+
+        let mut left = Reducer::new();
+        let mut right = Reducer::new();
+
+        left.push(b"ab");
+        right.push(b"BAcdef");
+        left.concat(right);
+
+        assert_eq!(b"cdef", &left.to_vec()[..]);
+
+        // Reactions can run across several joined chunks
+        let mut joined = Reducer::new();
+
+        for chunk in [&b"xaB"[..], b"c", b"", b"de", b"EDC"].iter() {
+            let mut reducer = Reducer::new();
+            reducer.push(chunk);
+            joined.concat(reducer);
+        }
+
+        joined.push(b"bA");
+        assert_eq!(b"x", &joined.to_vec()[..]);
+        assert_eq!(1, joined.len());
+
+        joined.push(b"X");
+        assert!(joined.is_empty());
+    }
+
+    #[test]
+    fn test_reactions() {
+        let polymer = b"dabAcCaCBAcCcaDA";
+        let mut reactions = reactions(polymer, &OppositeCase);
+
+        assert_eq!(Some(Reaction { position: 4, pair: (b'c', b'C'), remaining: 14 }), reactions.next());
+        assert_eq!(b"dabAaCBAcCcaDA", &reactions.polymer()[..]);
+        assert_eq!(Some(Reaction { position: 3, pair: (b'A', b'a'), remaining: 12 }), reactions.next());
+        assert_eq!(b"dabCBAcCcaDA", &reactions.polymer()[..]);
+        assert_eq!(Some(Reaction { position: 6, pair: (b'c', b'C'), remaining: 10 }), reactions.next());
+        assert_eq!(b"dabCBAcaDA", &reactions.polymer()[..]);
+        assert_eq!(None, reactions.next());
+        assert_eq!(b"dabCBAcaDA", &reactions.polymer()[..]);
+
+        assert_eq!("dabAcCaCBAcCcaDA
+dabAaCBAcCcaDA    removed cC at 4
+dabCBAcCcaDA      removed Aa at 3
+dabCBAcaDA        removed cC at 6
+", trace(polymer));
+
+        assert_eq!(0, super::reactions(b"", &OppositeCase).count());
+    }
+
     #[test]
     fn test_part1() {
-        assert_eq!(10, Part1::solve("dabAcCaCBAcCcaDA"));
+        assert_eq!(10, Part1::solve("dabAcCaCBAcCcaDA").unwrap());
+        assert_eq!(10, Part1::solve("dabAcCaCBAcCcaDA\n").unwrap());
+        assert_eq!(0, Part1::solve("").unwrap());
+        assert!(Part1::solve("dabAcCaC-BAcCcaDA").is_err());
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(4, Part2::solve("dabAcCaCBAcCcaDA"));
+        assert_eq!(Removal { unit: 'c', length: 4 }, Part2::solve("dabAcCaCBAcCcaDA").unwrap());
+        assert_eq!(Removal { unit: 'a', length: 0 }, Part2::solve("").unwrap());
+        assert_eq!("4 (removed c/C)", Part2::solve("dabAcCaCBAcCcaDA").unwrap().to_string());
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,155 @@
+use super::*;
+
+use std::collections::HashSet;
+
+use grid::{Grid, Point};
+use parse;
+
+pub struct Part1<T>(::std::marker::PhantomData<T>);
+
+impl<T> Solve<T> for Part1<T>
+    where T: AsRef<str> {
+    type Output = Result<usize, Error>;
+
+    fn solve(input: T) -> <Self as Solve<T>>::Output {
+        let coordinates = read_coordinates(input.as_ref())?;
+        let (min, max) = bounds(&coordinates).ok_or("No coordinates")?;
+
+        let owners: Grid<Option<usize>> = Grid::from_fn(
+            (max.x - min.x + 1) as usize, (max.y - min.y + 1) as usize,
+            |point| closest(&coordinates, point + min));
+
+        // Anything owning a square on the edge of the bounding box owns every square beyond it
+        // in that direction too
+        let infinite: HashSet<usize> = owners.iter()
+            .filter(|&(point, _owner)| {
+                point.x == 0 || point.y == 0
+                    || point.x as usize == owners.width() - 1 || point.y as usize == owners.height() - 1
+            })
+            .filter_map(|(_point, owner)| *owner)
+            .collect();
+
+        let mut areas = vec![0_usize; coordinates.len()];
+
+        for (_point, owner) in owners.iter() {
+            if let Some(owner) = *owner {
+                areas[owner] += 1;
+            }
+        }
+
+        Ok(areas.iter()
+            .enumerate()
+            .filter(|(idx, _area)| !infinite.contains(idx))
+            .map(|(_idx, area)| *area)
+            .max()
+            .unwrap_or(0))
+    }
+}
+
+pub struct Part2<T>(::std::marker::PhantomData<T>);
+
+/// Takes the coordinates along with the total distance the region must stay below.
+impl<T> Solve<(T, usize)> for Part2<T>
+    where T: AsRef<str> {
+    type Output = Result<usize, Error>;
+
+    fn solve((input, max_distance): (T, usize)) -> <Self as Solve<(T, usize)>>::Output {
+        let coordinates = read_coordinates(input.as_ref())?;
+        let (min, max) = bounds(&coordinates).ok_or("No coordinates")?;
+
+        // A square `d` outside the bounding box is at least `d` further from every coordinate,
+        // so the region can't extend further out than this
+        let margin = (max_distance / coordinates.len()) as isize;
+        let (min, max) = (min - Point::new(margin, margin), max + Point::new(margin, margin));
+
+        let mut size: usize = 0;
+
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let point = Point::new(x, y);
+
+                let total: usize = coordinates.iter()
+                    .map(|c| c.manhattan(point))
+                    .sum();
+
+                if total < max_distance {
+                    size += 1;
+                }
+            }
+        }
+
+        Ok(size)
+    }
+}
+
+fn read_coordinates(input: &str) -> Result<Vec<Point>, Error> {
+    parse::lines(input.lines(), |line| {
+        let (x, y) = parse::template("{}, {}", line)?;
+
+        Ok(Point::new(x, y))
+    })
+}
+
+/// The top left and bottom right corners of the smallest box containing every coordinate.
+fn bounds(coordinates: &[Point]) -> Option<(Point, Point)> {
+    let min_x = coordinates.iter().map(|c| c.x).min()?;
+    let min_y = coordinates.iter().map(|c| c.y).min()?;
+    let max_x = coordinates.iter().map(|c| c.x).max()?;
+    let max_y = coordinates.iter().map(|c| c.y).max()?;
+
+    Some((Point::new(min_x, min_y), Point::new(max_x, max_y)))
+}
+
+/// Index of the coordinate closest to `point`, or `None` if several are equally close.
+fn closest(coordinates: &[Point], point: Point) -> Option<usize> {
+    let mut closest: Option<usize> = None;
+    let mut min_distance = usize::MAX;
+
+    for (idx, c) in coordinates.iter().enumerate() {
+        let distance = c.manhattan(point);
+
+        if distance < min_distance {
+            min_distance = distance;
+            closest = Some(idx);
+        } else if distance == min_distance {
+            closest = None;
+        }
+    }
+
+    closest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "1, 1
+1, 6
+8, 3
+3, 4
+5, 5
+8, 9";
+
+    #[test]
+    fn test_closest() {
+        let coordinates = read_coordinates(EXAMPLE).unwrap();
+
+        assert_eq!(Some(0), closest(&coordinates, Point::new(0, 0)));
+        assert_eq!(Some(4), closest(&coordinates, Point::new(5, 2)));
+        assert_eq!(None, closest(&coordinates, Point::new(0, 4)));
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(17, Part1::solve(EXAMPLE).unwrap());
+        assert!(Part1::solve("").is_err());
+        assert!(Part1::solve("1, 1\n2 2").is_err());
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(16, Part2::solve((EXAMPLE, 32)).unwrap());
+        assert_eq!(1, Part2::solve(("0, 0", 1)).unwrap());
+        assert_eq!(5, Part2::solve(("0, 0", 2)).unwrap());
+    }
+}
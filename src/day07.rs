@@ -0,0 +1,241 @@
+use super::*;
+
+use std::fmt;
+use std::str::FromStr;
+use std::collections::{BTreeMap, BTreeSet};
+
+use parse;
+
+pub struct Part1<T>(::std::marker::PhantomData<T>);
+
+impl<T> Solve<T> for Part1<T>
+    where T: AsRef<str> {
+    type Output = Result<String, Error>;
+
+    fn solve(input: T) -> <Self as Solve<T>>::Output {
+        Instructions::from_str(input.as_ref())?.order()
+    }
+}
+
+pub struct Part2<T>(::std::marker::PhantomData<T>);
+
+/// Takes the instructions along with the number of workers and the base duration of each step.
+impl<T> Solve<(T, usize, u32)> for Part2<T>
+    where T: AsRef<str> {
+    type Output = Result<u32, Error>;
+
+    fn solve((input, workers, base_duration): (T, usize, u32)) -> <Self as Solve<(T, usize, u32)>>::Output {
+        let schedule = Instructions::from_str(input.as_ref())?.schedule(workers, base_duration)?;
+
+        Ok(schedule.total)
+    }
+}
+
+/// The steps of the sleigh kit, and the steps each one requires to be finished first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instructions {
+    requirements: BTreeMap<char, BTreeSet<char>>,
+}
+
+impl Instructions {
+    /// The order a single worker completes the steps in, always picking the first available
+    /// step alphabetically.
+    pub fn order(&self) -> Result<String, Error> {
+        let mut remaining = self.requirements.clone();
+        let mut order = String::new();
+
+        while let Some(step) = self.next_available(&remaining, &order) {
+            remaining.remove(&step);
+            order.push(step);
+        }
+
+        if !remaining.is_empty() {
+            return Err(From::from("Steps have cyclic requirements"));
+        }
+
+        Ok(order)
+    }
+
+    /// Simulates `workers` working on the steps in parallel, where each step takes
+    /// `base_duration` seconds plus its position in the alphabet.
+    pub fn schedule(&self, workers: usize, base_duration: u32) -> Result<Schedule, Error> {
+        if workers == 0 {
+            return Err(From::from("At least one worker is needed"));
+        }
+
+        let mut remaining = self.requirements.clone();
+        let mut busy: Vec<Option<(char, u32)>> = vec![None; workers];
+        let mut done = String::new();
+        let mut timeline: Vec<Second> = Vec::new();
+
+        loop {
+            for slot in busy.iter_mut().filter(|slot| slot.is_none()) {
+                match self.next_available(&remaining, &done) {
+                    Some(step) => {
+                        remaining.remove(&step);
+                        *slot = Some((step, base_duration + (step as u32 - 'A' as u32 + 1)));
+                    },
+                    None => break,
+                }
+            }
+
+            if busy.iter().all(|slot| slot.is_none()) {
+                break;
+            }
+
+            timeline.push(Second {
+                time: timeline.len() as u32,
+                workers: busy.iter().map(|slot| slot.map(|(step, _left)| step)).collect(),
+                done: done.clone(),
+            });
+
+            for slot in busy.iter_mut() {
+                if let Some((step, left)) = *slot {
+                    *slot = if left > 1 { Some((step, left - 1)) } else { None };
+
+                    if left == 1 {
+                        done.push(step);
+                    }
+                }
+            }
+        }
+
+        if !remaining.is_empty() {
+            return Err(From::from("Steps have cyclic requirements"));
+        }
+
+        Ok(Schedule {
+            total: timeline.len() as u32,
+            timeline,
+        })
+    }
+
+    /// The first step alphabetically that hasn't been started and whose requirements are done.
+    fn next_available(&self, remaining: &BTreeMap<char, BTreeSet<char>>, done: &str) -> Option<char> {
+        remaining.iter()
+            .find(|(_step, requires)| requires.iter().all(|r| done.contains(*r)))
+            .map(|(step, _requires)| *step)
+    }
+}
+
+impl FromStr for Instructions {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        let pairs: Vec<(char, char)> = parse::lines(s.lines(), |line| {
+            parse::template("Step {} must be finished before step {} can begin.", line)
+        })?;
+
+        let mut requirements: BTreeMap<char, BTreeSet<char>> = BTreeMap::new();
+
+        for (before, after) in pairs {
+            if !before.is_ascii_uppercase() || !after.is_ascii_uppercase() {
+                return Err(From::from(format!("Invalid step {} -> {}", before, after)));
+            }
+
+            requirements.entry(before).or_default();
+            requirements.entry(after).or_default().insert(before);
+        }
+
+        Ok(Self {
+            requirements,
+        })
+    }
+}
+
+/// The result of `Instructions::schedule`: how long all the steps took, and who was working on
+/// what at every second.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    pub total: u32,
+    pub timeline: Vec<Second>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Second {
+    pub time: u32,
+    /// The step each worker is working on, if any.
+    pub workers: Vec<Option<char>>,
+    /// Steps finished before this second began, in the order they finished.
+    pub done: String,
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let workers = self.timeline.first().map_or(0, |second| second.workers.len());
+
+        let mut header = String::from("Second");
+
+        for worker in 1..=workers {
+            header.push_str(&format!("   Worker {}", worker));
+        }
+
+        writeln!(f, "{}   Done", header)?;
+
+        for second in self.timeline.iter() {
+            let mut row = format!("{:>4}  ", second.time);
+
+            for step in second.workers.iter() {
+                row.push_str(&format!("{:^11}", step.unwrap_or('.')));
+            }
+
+            writeln!(f, "{}  {}", row, second.done)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "Step C must be finished before step A can begin.
+Step C must be finished before step F can begin.
+Step A must be finished before step B can begin.
+Step A must be finished before step D can begin.
+Step B must be finished before step E can begin.
+Step D must be finished before step E can begin.
+Step F must be finished before step E can begin.";
+
+    #[test]
+    fn test_schedule() {
+        let schedule = Instructions::from_str(EXAMPLE).unwrap().schedule(2, 0).unwrap();
+
+        assert_eq!(15, schedule.total);
+        assert_eq!(15, schedule.timeline.len());
+        assert_eq!(vec![Some('C'), None], schedule.timeline[0].workers);
+        assert_eq!(Second { time: 3, workers: vec![Some('A'), Some('F')], done: "C".to_owned() },
+                   schedule.timeline[3]);
+        assert_eq!("CABFD", schedule.timeline[14].done);
+
+        let rendered = schedule.to_string();
+        let lines: Vec<&str> = rendered.lines().collect();
+
+        assert_eq!("Second   Worker 1   Worker 2   Done", lines[0]);
+        assert_eq!("   3       A          F       C", lines[4]);
+        assert_eq!("  14       E          .       CABFD", lines[15]);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let cycle = "Step A must be finished before step B can begin.
+Step B must be finished before step A can begin.";
+
+        assert!(Part1::solve(cycle).is_err());
+        assert!(Part2::solve((cycle, 2, 0)).is_err());
+        assert!(Part2::solve((EXAMPLE, 0, 0)).is_err());
+        assert!(Part1::solve("Step a must be finished before step B can begin.").is_err());
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!("CABDFE", Part1::solve(EXAMPLE).unwrap());
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(15, Part2::solve((EXAMPLE, 2, 0)).unwrap());
+        assert_eq!(21, Part2::solve((EXAMPLE, 1, 0)).unwrap());
+    }
+}
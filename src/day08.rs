@@ -0,0 +1,226 @@
+use super::*;
+
+use std::fmt;
+use std::str::FromStr;
+
+use parse;
+
+pub struct Part1<T>(::std::marker::PhantomData<T>);
+
+impl<T> Solve<T> for Part1<T>
+    where T: AsRef<str> {
+    type Output = Result<usize, Error>;
+
+    fn solve(input: T) -> <Self as Solve<T>>::Output {
+        let tree = Tree::from_str(input.as_ref())?;
+
+        Ok(tree.nodes().map(|node| node.metadata.iter().sum::<usize>()).sum())
+    }
+}
+
+pub struct Part2<T>(::std::marker::PhantomData<T>);
+
+impl<T> Solve<T> for Part2<T>
+    where T: AsRef<str> {
+    type Output = Result<usize, Error>;
+
+    fn solve(input: T) -> <Self as Solve<T>>::Output {
+        Ok(Tree::from_str(input.as_ref())?.value())
+    }
+}
+
+/// A node of the license tree. Children refer to other nodes of the same `Tree` by index.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Node {
+    pub children: Vec<usize>,
+    pub metadata: Vec<usize>,
+}
+
+/// The license tree, with every node stored in the order its header appears in the input, so
+/// the root is node 0 and every child comes after its parent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tree {
+    nodes: Vec<Node>,
+}
+
+impl Tree {
+    pub fn root(&self) -> &Node {
+        &self.nodes[0]
+    }
+
+    pub fn node(&self, idx: usize) -> Option<&Node> {
+        self.nodes.get(idx)
+    }
+
+    /// Every node, in depth-first order.
+    pub fn nodes(&self) -> impl Iterator<Item = &Node> {
+        self.nodes.iter()
+    }
+
+    /// Every node along with its depth below the root, in depth-first order.
+    pub fn walk(&self) -> Walk<'_> {
+        Walk {
+            tree: self,
+            stack: vec![(0, 0)],
+        }
+    }
+
+    /// The value of the root: a leaf is worth the sum of its metadata, and any other node the
+    /// sum of the values of the children its metadata entries refer to, starting at 1.
+    pub fn value(&self) -> usize {
+        let mut values = vec![0; self.nodes.len()];
+
+        // Children always come after their parent, so working backwards every child's value is
+        // known before it's needed
+        for (idx, node) in self.nodes.iter().enumerate().rev() {
+            values[idx] = if node.children.is_empty() {
+                node.metadata.iter().sum()
+            } else {
+                node.metadata.iter()
+                    .filter_map(|&entry| entry.checked_sub(1).and_then(|i| node.children.get(i)))
+                    .map(|&child| values[child])
+                    .sum()
+            };
+        }
+
+        values[0]
+    }
+}
+
+impl FromStr for Tree {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        let numbers: Vec<usize> = parse::ints(s)?;
+        let mut numbers = numbers.into_iter();
+
+        let mut nodes: Vec<Node> = vec![Node::default()];
+
+        // Each entry is a node still being read, with the number of children it has left to read
+        // and how many metadata entries follow them
+        let (children, metadata) = header(&mut numbers)?;
+        let mut stack: Vec<(usize, usize, usize)> = vec![(0, children, metadata)];
+
+        while let Some((idx, children, metadata)) = stack.pop() {
+            if children > 0 {
+                let child = nodes.len();
+                nodes.push(Node::default());
+                nodes[idx].children.push(child);
+
+                let (grandchildren, child_metadata) = header(&mut numbers)?;
+
+                stack.push((idx, children - 1, metadata));
+                stack.push((child, grandchildren, child_metadata));
+            } else {
+                for _ in 0..metadata {
+                    let entry = numbers.next().ok_or("Unexpected end of input in node metadata")?;
+                    nodes[idx].metadata.push(entry);
+                }
+            }
+        }
+
+        if numbers.next().is_some() {
+            return Err(From::from("Unexpected trailing numbers after the root node"));
+        }
+
+        Ok(Self {
+            nodes,
+        })
+    }
+}
+
+/// Reads the number of children and metadata entries of the next node.
+fn header<I>(numbers: &mut I) -> Result<(usize, usize), Error>
+    where I: Iterator<Item = usize> {
+
+    match (numbers.next(), numbers.next()) {
+        (Some(children), Some(metadata)) => Ok((children, metadata)),
+        _ => Err(From::from("Unexpected end of input in node header")),
+    }
+}
+
+/// Iterator over the nodes of a `Tree` with their depths, see `Tree::walk`.
+pub struct Walk<'a> {
+    tree: &'a Tree,
+    stack: Vec<(usize, usize)>,
+}
+
+impl<'a> Iterator for Walk<'a> {
+    type Item = (usize, &'a Node);
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        let (idx, depth) = self.stack.pop()?;
+        let node = &self.tree.nodes[idx];
+
+        self.stack.extend(node.children.iter().rev().map(|&child| (child, depth + 1)));
+
+        Some((depth, node))
+    }
+}
+
+/// Draws the tree one node per line, indented by depth, e.g. `- 2 children, metadata 1 1 2`.
+impl fmt::Display for Tree {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        for (depth, node) in self.walk() {
+            let metadata: Vec<String> = node.metadata.iter().map(|m| m.to_string()).collect();
+
+            writeln!(f, "{:indent$}- {} children, metadata {}", "", node.children.len(), metadata.join(" "),
+                     indent = depth * 2)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2";
+
+    #[test]
+    fn test_tree() {
+        let tree = Tree::from_str(EXAMPLE).unwrap();
+
+        assert_eq!(&Node { children: vec![1, 2], metadata: vec![1, 1, 2] }, tree.root());
+        assert_eq!(Some(&Node { children: vec![], metadata: vec![99] }), tree.node(3));
+        assert_eq!(vec![0, 1, 1, 2], tree.walk().map(|(depth, _node)| depth).collect::<Vec<_>>());
+
+        assert_eq!("\
+- 2 children, metadata 1 1 2
+  - 0 children, metadata 10 11 12
+  - 1 children, metadata 2
+    - 0 children, metadata 99
+", tree.to_string());
+    }
+
+    #[test]
+    fn test_deep_tree() {
+        // Deep enough to overflow the stack if parsed recursively
+        let depth = 100_000;
+        let input = format!("{}0 1 7{}", "1 1 ".repeat(depth), " 1".repeat(depth));
+        let tree = Tree::from_str(&input).unwrap();
+
+        assert_eq!(depth + 1, tree.nodes().count());
+        assert_eq!(7, tree.value());
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(Tree::from_str("").is_err());
+        assert!(Tree::from_str("1 1 0").is_err());
+        assert!(Tree::from_str("0 2 1").is_err());
+        assert!(Tree::from_str("0 1 1 5").is_err());
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(138, Part1::solve(EXAMPLE).unwrap());
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(66, Part2::solve(EXAMPLE).unwrap());
+        assert_eq!(0, Part2::solve("1 1 0 1 5 2").unwrap());
+    }
+}
@@ -0,0 +1,112 @@
+use super::*;
+
+use std::str::FromStr;
+use std::collections::VecDeque;
+
+use parse;
+
+pub struct Part1<T>(::std::marker::PhantomData<T>);
+
+impl<T> Solve<T> for Part1<T>
+    where T: AsRef<str> {
+    type Output = Result<u64, Error>;
+
+    fn solve(input: T) -> <Self as Solve<T>>::Output {
+        let game = Game::from_str(input.as_ref())?;
+
+        Ok(high_score(game.players, game.last_marble))
+    }
+}
+
+pub struct Part2<T>(::std::marker::PhantomData<T>);
+
+impl<T> Solve<T> for Part2<T>
+    where T: AsRef<str> {
+    type Output = Result<u64, Error>;
+
+    fn solve(input: T) -> <Self as Solve<T>>::Output {
+        let game = Game::from_str(input.as_ref())?;
+
+        Ok(high_score(game.players, game.last_marble * 100))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Game {
+    pub players: usize,
+    pub last_marble: u64,
+}
+
+impl FromStr for Game {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        let (players, last_marble) = parse::template("{} players; last marble is worth {} points", s.trim())?;
+
+        if players == 0 {
+            return Err(From::from("At least one player is needed"));
+        }
+
+        Ok(Self {
+            players,
+            last_marble,
+        })
+    }
+}
+
+/// The winning score once `last_marble` has been played by `players` taking turns.
+///
+/// The circle is kept rotated so the current marble is always at the back, which makes every
+/// move a constant number of rotations, pushes and pops.
+pub fn high_score(players: usize, last_marble: u64) -> u64 {
+    let mut circle: VecDeque<u64> = VecDeque::with_capacity(last_marble as usize + 1);
+    let mut scores = vec![0_u64; players];
+
+    circle.push_back(0);
+
+    for marble in 1..=last_marble {
+        if marble % 23 == 0 {
+            circle.rotate_right(7 % circle.len());
+
+            let removed = circle.pop_back().unwrap_or(0);
+            scores[((marble - 1) % players as u64) as usize] += marble + removed;
+
+            if !circle.is_empty() {
+                circle.rotate_left(1);
+            }
+        } else {
+            circle.rotate_left(1 % circle.len());
+            circle.push_back(marble);
+        }
+    }
+
+    scores.into_iter().max().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_high_score() {
+        assert_eq!(32, high_score(9, 25));
+        assert_eq!(8317, high_score(10, 1618));
+        assert_eq!(146373, high_score(13, 7999));
+        assert_eq!(2764, high_score(17, 1104));
+        assert_eq!(54718, high_score(21, 6111));
+        assert_eq!(37305, high_score(30, 5807));
+        assert_eq!(0, high_score(1, 0));
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(32, Part1::solve("9 players; last marble is worth 25 points\n").unwrap());
+        assert!(Part1::solve("0 players; last marble is worth 25 points").is_err());
+        assert!(Part1::solve("9 players; last marble is worth 25").is_err());
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(high_score(10, 1600), Part2::solve("10 players; last marble is worth 16 points").unwrap());
+    }
+}
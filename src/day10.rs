@@ -0,0 +1,355 @@
+use super::*;
+
+use std::str::FromStr;
+use std::collections::HashSet;
+
+use grid::{Grid, Point};
+use parse;
+
+pub struct Part1<T>(::std::marker::PhantomData<T>);
+
+impl<T> Solve<T> for Part1<T>
+    where T: AsRef<str> {
+    type Output = Result<String, Error>;
+
+    fn solve(input: T) -> <Self as Solve<T>>::Output {
+        let sky = Sky::from_str(input.as_ref())?;
+
+        read_message(&sky.render(sky.message_time()))
+    }
+}
+
+pub struct Part2<T>(::std::marker::PhantomData<T>);
+
+impl<T> Solve<T> for Part2<T>
+    where T: AsRef<str> {
+    type Output = Result<isize, Error>;
+
+    fn solve(input: T) -> <Self as Solve<T>>::Output {
+        Ok(Sky::from_str(input.as_ref())?.message_time())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Particle {
+    pub position: Point,
+    pub velocity: Point,
+}
+
+impl Particle {
+    pub fn at(&self, time: isize) -> Point {
+        Point::new(self.position.x + self.velocity.x * time, self.position.y + self.velocity.y * time)
+    }
+}
+
+impl FromStr for Particle {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        // The values are padded to line up, so pick the numbers out rather than using a template
+        match parse::ints::<isize>(s)?.as_slice() {
+            &[x, y, dx, dy] if s.starts_with("position=<") && s.contains("> velocity=<") => Ok(Self {
+                position: Point::new(x, y),
+                velocity: Point::new(dx, dy),
+            }),
+            _ => Err(From::from(format!("Invalid particle {}", s))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sky {
+    pub particles: Vec<Particle>,
+}
+
+impl Sky {
+    /// Where every particle is after `time` seconds.
+    pub fn at(&self, time: isize) -> Vec<Point> {
+        self.particles.iter().map(|p| p.at(time)).collect()
+    }
+
+    /// The second at which the particles are closest together, judged by the width plus height
+    /// of their bounding box.
+    ///
+    /// Both the width and height are maxima of linear functions of time minus minima of them, so
+    /// their sum only decreases until it reaches its minimum and then only increases, and the
+    /// minimum can be found by bisecting on whether the next second is any smaller.
+    pub fn message_time(&self) -> isize {
+        let spread = |time: isize| bounds(&self.at(time)).map_or(0, |(min, max)| (max.x - min.x) + (max.y - min.y));
+        let shrinking = |time: isize| spread(time + 1) < spread(time);
+
+        let mut high: isize = 1;
+
+        while shrinking(high) {
+            high *= 2;
+        }
+
+        let mut low: isize = 0;
+
+        // The first second from which the spread stops shrinking lies in `low..=high`
+        while low < high {
+            let mid = low + (high - low) / 2;
+
+            if shrinking(mid) {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        low
+    }
+
+    /// Draws the particles after `time` seconds, cropped to their bounding box, with `#` for a
+    /// lit square and `.` for an empty one.
+    pub fn render(&self, time: isize) -> String {
+        let points = self.at(time);
+
+        let (min, max) = match bounds(&points) {
+            Some(bounds) => bounds,
+            None => return String::new(),
+        };
+
+        let lit: HashSet<Point> = points.into_iter().collect();
+        let grid = Grid::from_fn((max.x - min.x + 1) as usize, (max.y - min.y + 1) as usize,
+                                 |point| if lit.contains(&(point + min)) { '#' } else { '.' });
+
+        grid.to_string()
+    }
+}
+
+impl FromStr for Sky {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        Ok(Self {
+            particles: parse::lines(s.lines(), Particle::from_str)?,
+        })
+    }
+}
+
+fn bounds(points: &[Point]) -> Option<(Point, Point)> {
+    let min_x = points.iter().map(|p| p.x).min()?;
+    let min_y = points.iter().map(|p| p.y).min()?;
+    let max_x = points.iter().map(|p| p.x).max()?;
+    let max_y = points.iter().map(|p| p.y).max()?;
+
+    Some((Point::new(min_x, min_y), Point::new(max_x, max_y)))
+}
+
+const GLYPH_WIDTH: usize = 6;
+const GLYPH_HEIGHT: usize = 10;
+const GLYPH_SPACING: usize = 2;
+
+/// The letters of the font the messages are written in, as far as they're known.
+const GLYPHS: &[(char, [&str; GLYPH_HEIGHT])] = &[
+    ('A', ["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', ["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', [".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', [".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', ["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', ["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+    ('K', ["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', ["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', ["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', ["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', ["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', ["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', ["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+/// Reads the letters out of a message drawn by `Sky::render`.
+///
+/// Blank columns around the message are ignored. A letter's own first or last column can be
+/// blank too, so the letters are lined up by trying each way of putting the columns that are
+/// missing back on either side.
+pub fn read_message(message: &str) -> Result<String, Error> {
+    let rows: Vec<&str> = message.lines().collect();
+
+    if rows.len() != GLYPH_HEIGHT {
+        return Err(From::from(format!("Expected a message {} rows tall, found {}", GLYPH_HEIGHT, rows.len())));
+    }
+
+    let width = rows[0].len();
+
+    if rows.iter().any(|row| row.len() != width || !row.bytes().all(|b| b == b'#' || b == b'.')) {
+        return Err(From::from("Message rows must be the same width and only contain `#` and `.`"));
+    }
+
+    let lit = |x: &usize| rows.iter().any(|row| row.as_bytes()[*x] == b'#');
+    let first = (0..width).find(&lit).ok_or("Message is blank")?;
+    let last = (0..width).rev().find(&lit).ok_or("Message is blank")?;
+
+    let pitch = GLYPH_WIDTH + GLYPH_SPACING;
+    let trimmed = last - first + 1;
+    let letters = (trimmed + GLYPH_SPACING).div_ceil(pitch);
+    let missing = letters * pitch - GLYPH_SPACING - trimmed;
+
+    let mut error: Option<Error> = None;
+
+    for left in 0..=missing {
+        let padded: Vec<String> = rows.iter()
+            .map(|row| format!("{}{}{}", ".".repeat(left), &row[first..=last], ".".repeat(missing - left)))
+            .collect();
+
+        match read_letters(&padded, letters) {
+            Ok(letters) => return Ok(letters),
+            Err(e) => error = error.or(Some(e)),
+        }
+    }
+
+    Err(error.unwrap_or_else(|| From::from("Message is blank")))
+}
+
+/// Reads `letters` letters out of rows that line up exactly with the font.
+fn read_letters(rows: &[String], letters: usize) -> Result<String, Error> {
+    (0..letters)
+        .map(|idx| {
+            let start = idx * (GLYPH_WIDTH + GLYPH_SPACING);
+            let glyph: Vec<&str> = rows.iter().map(|row| &row[start..start + GLYPH_WIDTH]).collect();
+
+            GLYPHS.iter()
+                .find(|(_letter, pattern)| pattern[..] == glyph[..])
+                .map(|(letter, _pattern)| *letter)
+                .ok_or_else(|| From::from(format!("Unknown letter {}:\n{}", idx + 1, glyph.join("\n"))))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "position=< 9,  1> velocity=< 0,  2>
+position=< 7,  0> velocity=<-1,  0>
+position=< 3, -2> velocity=<-1,  1>
+position=< 6, 10> velocity=<-2, -1>
+position=< 2, -4> velocity=< 2,  2>
+position=<-6, 10> velocity=< 2, -2>
+position=< 1,  8> velocity=< 1, -1>
+position=< 1,  7> velocity=< 1,  0>
+position=<-3, 11> velocity=< 1, -2>
+position=< 7,  6> velocity=<-1, -1>
+position=<-2,  3> velocity=< 1,  0>
+position=<-4,  3> velocity=< 2,  0>
+position=<10, -3> velocity=<-1,  1>
+position=< 5, 11> velocity=< 1, -2>
+position=< 4,  7> velocity=< 0, -1>
+position=< 8, -2> velocity=< 0,  1>
+position=<15,  0> velocity=<-2,  0>
+position=< 1,  6> velocity=< 1,  0>
+position=< 8,  9> velocity=< 0, -1>
+position=< 3,  3> velocity=<-1,  1>
+position=< 0,  5> velocity=< 0, -1>
+position=<-2,  2> velocity=< 2,  0>
+position=< 5, -2> velocity=< 1,  2>
+position=< 1,  4> velocity=< 2,  1>
+position=<-2,  7> velocity=< 2, -2>
+position=< 3,  6> velocity=<-1, -1>
+position=< 5,  0> velocity=< 1,  0>
+position=<-6,  0> velocity=< 2,  0>
+position=< 5,  9> velocity=< 1, -2>
+position=<14,  7> velocity=<-2,  0>
+position=<-3,  6> velocity=< 2, -1>";
+
+    #[test]
+    fn test_render() {
+        let sky = Sky::from_str(EXAMPLE).unwrap();
+
+        assert_eq!(3, sky.message_time());
+        assert_eq!("\
+#...#..###
+#...#...#.
+#...#...#.
+#####...#.
+#...#...#.
+#...#...#.
+#...#...#.
+#...#..###
+", sky.render(3));
+
+        assert_eq!("", Sky::from_str("").unwrap().render(0));
+        assert!(Sky::from_str("position=< 9,  1> velocity=< 0>").is_err());
+    }
+
+    #[test]
+    fn test_read_message() {
+        let rows: Vec<String> = (0..GLYPH_HEIGHT)
+            .map(|row| ["H", "Z", "J"].iter()
+                .map(|letter| GLYPHS.iter().find(|(l, _)| l.to_string() == *letter).unwrap().1[row])
+                .collect::<Vec<_>>()
+                .join(".."))
+            .collect();
+
+        assert_eq!("HZJ", read_message(&rows.join("\n")).unwrap());
+        assert!(read_message(&rows[..9].join("\n")).is_err());
+
+        let unknown = rows.iter().map(|row| format!("{}..######", row)).collect::<Vec<_>>().join("\n");
+        assert!(read_message(&unknown).is_err());
+    }
+
+    #[test]
+    fn test_read_message_margins() {
+        let message = "\
+######..#....#
+.....#..#....#
+.....#...#..#.
+....#....#..#.
+...#......##..
+..#.......##..
+.#.......#..#.
+#........#..#.
+#.......#....#
+######..#....#";
+
+        assert_eq!("ZX", read_message(message).unwrap());
+
+        let padded: Vec<String> = message.lines().map(|row| format!(".{}..", row)).collect();
+        assert_eq!("ZX", read_message(&padded.join("\n")).unwrap());
+
+        // An X with its last column blanked out no longer fits the font anywhere
+        let cropped: Vec<String> = message.lines().map(|row| format!("{}.", &row[..row.len() - 1])).collect();
+        assert!(read_message(&cropped.join("\n")).is_err());
+
+        assert!(read_message(&[".."; GLYPH_HEIGHT].join("\n")).is_err());
+    }
+
+    #[test]
+    fn test_part1() {
+        let message = "\
+#.......######
+#............#
+#............#
+#...........#.
+#..........#..
+#.........#...
+#........#....
+#.......#.....
+#.......#.....
+######..######";
+
+        // Scatter the stars so they only come together after 4 seconds
+        let input: Vec<String> = message.lines()
+            .enumerate()
+            .flat_map(|(y, row)| row.chars()
+                .enumerate()
+                .filter(|&(_x, c)| c == '#')
+                .map(move |(x, _c)| (x as isize, y as isize)))
+            .enumerate()
+            .map(|(idx, (x, y))| {
+                let (dx, dy) = ((idx % 5) as isize - 2, (idx % 3) as isize - 1);
+                format!("position=<{:>3}, {:>3}> velocity=<{:>2}, {:>2}>", x - 4 * dx, y - 4 * dy, dx, dy)
+            })
+            .collect();
+
+        assert_eq!("LZ", Part1::solve(input.join("\n")).unwrap());
+        assert_eq!(4, Part2::solve(input.join("\n")).unwrap());
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(3, Part2::solve(EXAMPLE).unwrap());
+    }
+}
@@ -0,0 +1,162 @@
+use super::*;
+
+use std::fmt;
+
+use grid::{Grid, Point};
+
+pub struct Part1<T>(::std::marker::PhantomData<T>);
+
+impl<T> Solve<T> for Part1<T>
+    where T: AsRef<str> {
+    type Output = Result<Point, Error>;
+
+    fn solve(input: T) -> <Self as Solve<T>>::Output {
+        let grid = FuelGrid::new(read_serial(input.as_ref())?);
+
+        Ok(grid.best_square(3).ok_or("Squares don't fit on the grid")?.corner)
+    }
+}
+
+pub struct Part2<T>(::std::marker::PhantomData<T>);
+
+impl<T> Solve<T> for Part2<T>
+    where T: AsRef<str> {
+    type Output = Result<Square, Error>;
+
+    fn solve(input: T) -> <Self as Solve<T>>::Output {
+        let grid = FuelGrid::new(read_serial(input.as_ref())?);
+
+        Ok((1..=GRID_SIZE)
+            .filter_map(|size| grid.best_square(size))
+            .max_by_key(|square| square.power)
+            .ok_or("Squares don't fit on the grid")?)
+    }
+}
+
+const GRID_SIZE: usize = 300;
+
+fn read_serial(input: &str) -> Result<i64, Error> {
+    input.trim().parse::<i64>()
+        .map_err(|e| From::from(format!("Invalid serial number {}: {}", input.trim(), e)))
+}
+
+/// The power level of the fuel cell at `x,y`, with coordinates starting at 1.
+pub fn power_level(x: i64, y: i64, serial: i64) -> i64 {
+    let rack_id = x + 10;
+
+    ((rack_id * y + serial) * rack_id / 100) % 10 - 5
+}
+
+/// A square of fuel cells, given by its top left corner and its size, along with its total power.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Square {
+    pub corner: Point,
+    pub size: usize,
+    pub power: i64,
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{},{}", self.corner, self.size)
+    }
+}
+
+/// The fuel cells for a serial number, stored as a summed-area table so that the total power of
+/// any square takes constant time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuelGrid {
+    /// Entry `x,y` is the total power of every cell above and to the left of it, exclusive, so
+    /// row and column 0 are all zero and coordinates line up with the puzzle's.
+    sums: Grid<i64>,
+}
+
+impl FuelGrid {
+    pub fn new(serial: i64) -> Self {
+        let mut sums = Grid::new(GRID_SIZE + 1, GRID_SIZE + 1, 0);
+
+        for y in 1..=GRID_SIZE as isize {
+            for x in 1..=GRID_SIZE as isize {
+                sums[Point::new(x, y)] = power_level(x as i64, y as i64, serial)
+                    + sums[Point::new(x - 1, y)] + sums[Point::new(x, y - 1)] - sums[Point::new(x - 1, y - 1)];
+            }
+        }
+
+        Self {
+            sums,
+        }
+    }
+
+    /// The total power of the `size` square with its top left corner at `corner`.
+    pub fn square_power(&self, corner: Point, size: usize) -> i64 {
+        let (x, y) = (corner.x - 1, corner.y - 1);
+        let size = size as isize;
+
+        self.sums[Point::new(x + size, y + size)] - self.sums[Point::new(x, y + size)]
+            - self.sums[Point::new(x + size, y)] + self.sums[Point::new(x, y)]
+    }
+
+    /// The `size` square with the most power, preferring the first in reading order on ties.
+    pub fn best_square(&self, size: usize) -> Option<Square> {
+        if size == 0 || size > GRID_SIZE {
+            return None;
+        }
+
+        let last = (GRID_SIZE - size + 1) as isize;
+        let mut best: Option<Square> = None;
+
+        for y in 1..=last {
+            for x in 1..=last {
+                let corner = Point::new(x, y);
+                let power = self.square_power(corner, size);
+
+                if best.is_none_or(|best| power > best.power) {
+                    best = Some(Square {
+                        corner,
+                        size,
+                        power,
+                    });
+                }
+            }
+        }
+
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_power_level() {
+        assert_eq!(4, power_level(3, 5, 8));
+        assert_eq!(-5, power_level(122, 79, 57));
+        assert_eq!(0, power_level(217, 196, 39));
+        assert_eq!(4, power_level(101, 153, 71));
+    }
+
+    #[test]
+    fn test_square_power() {
+        let grid = FuelGrid::new(18);
+
+        assert_eq!(29, grid.square_power(Point::new(33, 45), 3));
+        assert_eq!(power_level(300, 300, 18), grid.square_power(Point::new(300, 300), 1));
+        assert_eq!(None, grid.best_square(301));
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!("33,45", Part1::solve("18").unwrap().to_string());
+        assert_eq!("21,61", Part1::solve("42\n").unwrap().to_string());
+        assert!(Part1::solve("serial").is_err());
+    }
+
+    #[test]
+    fn test_part2() {
+        let square = Part2::solve("18").unwrap();
+
+        assert_eq!("90,269,16", square.to_string());
+        assert_eq!(113, square.power);
+        assert_eq!("232,251,12", Part2::solve("42").unwrap().to_string());
+    }
+}
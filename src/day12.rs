@@ -0,0 +1,271 @@
+use super::*;
+
+use std::fmt;
+use std::str::FromStr;
+
+use cycle;
+use parse;
+use parse::ParseError;
+
+pub struct Part1<T>(::std::marker::PhantomData<T>);
+
+impl<T> Solve<T> for Part1<T>
+    where T: AsRef<str> {
+    type Output = Result<i64, Error>;
+
+    fn solve(input: T) -> <Self as Solve<T>>::Output {
+        Ok(Garden::from_str(input.as_ref())?.simulate(20).sum())
+    }
+}
+
+pub struct Part2<T>(::std::marker::PhantomData<T>);
+
+impl<T> Solve<T> for Part2<T>
+    where T: AsRef<str> {
+    type Output = Result<i64, Error>;
+
+    fn solve(input: T) -> <Self as Solve<T>>::Output {
+        Garden::from_str(input.as_ref())?.sum_after(50_000_000_000)
+    }
+}
+
+/// How many generations to simulate looking for the pots to settle into a pattern.
+const MAX_GENERATIONS: usize = 10_000;
+
+/// A row of pots, stored from the first pot with a plant to the last.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Pots {
+    /// The number of the first pot in `plants`.
+    pub first: i64,
+    pub plants: Vec<bool>,
+}
+
+impl Pots {
+    fn new(first: i64, plants: Vec<bool>) -> Self {
+        let start = plants.iter().position(|&plant| plant).unwrap_or(plants.len());
+        let end = plants.iter().rposition(|&plant| plant).map_or(start, |end| end + 1);
+
+        Self {
+            first: first + start as i64,
+            plants: plants[start..end].to_vec(),
+        }
+    }
+
+    /// The sum of the numbers of the pots with plants in them.
+    pub fn sum(&self) -> i64 {
+        self.plants.iter()
+            .enumerate()
+            .filter(|(_idx, &plant)| plant)
+            .map(|(idx, _plant)| self.first + idx as i64)
+            .sum()
+    }
+
+    pub fn count(&self) -> usize {
+        self.plants.iter().filter(|&&plant| plant).count()
+    }
+}
+
+impl fmt::Display for Pots {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        for &plant in self.plants.iter() {
+            write!(f, "{}", if plant { '#' } else { '.' })?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The spreading rules, indexed by the five pots around a pot read as a binary number with the
+/// leftmost pot as the highest bit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules([bool; 32]);
+
+impl Rules {
+    pub fn next(&self, pots: &Pots) -> Pots {
+        let mut pattern: usize = 0;
+        let mut plants: Vec<bool> = Vec::with_capacity(pots.plants.len() + 4);
+
+        // A pot's neighborhood slides one pot right each time, so shift the next pot in and let
+        // the one that's left fall off the top
+        for &plant in pots.plants.iter().chain([false; 4].iter()) {
+            pattern = ((pattern << 1) | plant as usize) & 0b11111;
+            plants.push(self.0[pattern]);
+        }
+
+        Pots::new(pots.first - 2, plants)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Garden {
+    pub initial: Pots,
+    pub rules: Rules,
+}
+
+impl Garden {
+    /// The pots after `generations` generations, by simulating every one of them.
+    pub fn simulate(&self, generations: usize) -> Pots {
+        (0..generations).fold(self.initial.clone(), |pots, _| self.rules.next(&pots))
+    }
+
+    /// The sum of the pot numbers with plants after `generations` generations.
+    ///
+    /// Up to `MAX_GENERATIONS` this simulates every generation. Past that, it waits for the plants
+    /// to settle into a pattern that repeats, possibly shifted along the row, and extrapolates
+    /// from there.
+    pub fn sum_after(&self, generations: u64) -> Result<i64, Error> {
+        if generations <= MAX_GENERATIONS as u64 {
+            return Ok(self.simulate(generations as usize).sum());
+        }
+
+        let cycle = cycle::find(self.initial.clone(), |pots| self.rules.next(pots),
+                                |pots| (pots.plants.clone(), pots.first), MAX_GENERATIONS)
+            .ok_or("The plants never settle into a pattern")?;
+
+        let (pots, shift) = cycle.at(generations);
+
+        Ok(pots.sum() + shift * pots.count() as i64)
+    }
+}
+
+impl FromStr for Garden {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        let mut initial: Option<Vec<bool>> = None;
+        let mut rules = [false; 32];
+
+        // The first line holds the initial state, and every non-empty line after it a rule
+        parse::lines(s.lines(), |line| {
+            if initial.is_none() {
+                let fields = parse::fields("initial state: {}", line)?;
+                initial = Some(fields[0].parse_with(read_plants)?);
+            } else if !line.is_empty() {
+                let fields = parse::fields("{} => {}", line)?;
+                let pattern = fields[0].parse_with(|s| read_exactly(s, 5))?;
+                let result = fields[1].parse_with(|s| read_exactly(s, 1))?;
+
+                rules[pattern.iter().fold(0, |idx, &plant| (idx << 1) | plant as usize)] = result[0];
+            }
+
+            Ok(())
+        })?;
+
+        if rules[0] {
+            return Err(From::from("Plants can't grow in every empty pot"));
+        }
+
+        Ok(Self {
+            initial: Pots::new(0, initial.ok_or("Missing initial state")?),
+            rules: Rules(rules),
+        })
+    }
+}
+
+fn read_plants(s: &str) -> Result<Vec<bool>, Error> {
+    s.chars()
+        .enumerate()
+        .map(|(idx, c)| match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => Err(From::from(ParseError::at_column(idx + 1, format!("Invalid pot `{}`", c)))),
+        })
+        .collect()
+}
+
+fn read_exactly(s: &str, count: usize) -> Result<Vec<bool>, Error> {
+    let plants = read_plants(s)?;
+
+    if plants.len() != count {
+        return Err(From::from(format!("Expected {} pots, found {}", count, plants.len())));
+    }
+
+    Ok(plants)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "initial state: #..#.#..##......###...###
+
+...## => #
+..#.. => #
+.#... => #
+.#.#. => #
+.#.## => #
+.##.. => #
+.#### => #
+#.#.# => #
+#.### => #
+##.#. => #
+##.## => #
+###.. => #
+###.# => #
+####. => #";
+
+    #[test]
+    fn test_simulate() {
+        let garden = Garden::from_str(EXAMPLE).unwrap();
+
+        let pots = garden.simulate(1);
+        assert_eq!((0, "#...#....#.....#..#..#..#".to_owned()), (pots.first, pots.to_string()));
+
+        let pots = garden.simulate(20);
+        assert_eq!((-2, "#....##....#####...#######....#.#..##".to_owned()), (pots.first, pots.to_string()));
+        assert_eq!(325, pots.sum());
+    }
+
+    #[test]
+    fn test_sum_after() {
+        let garden = Garden::from_str(EXAMPLE).unwrap();
+
+        for &generations in [0, 1, 20, 86, 87, 150, 301].iter() {
+            assert_eq!(garden.simulate(generations).sum(), garden.sum_after(generations as u64).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(Garden::from_str("#..#").is_err());
+        assert!(Garden::from_str("initial state: #.x#").is_err());
+        assert!(Garden::from_str("initial state: #..#\n\n..... => #").is_err());
+        assert!(Garden::from_str("initial state: #..#\n\n...# => #").is_err());
+        assert!(Garden::from_str("").is_err());
+
+        let err = Garden::from_str("initial state: #.x#").unwrap_err();
+        assert_eq!("Line 1, column 18: Invalid pot `x`", err.to_string());
+
+        let err = Garden::from_str("initial state: #..#\n\n..#.. => #\n.#x.. => .").unwrap_err();
+        assert_eq!("Line 4, column 3: Invalid pot `x`", err.to_string());
+
+        let err = Garden::from_str("initial state: #..#\n\n..#.. => ##").unwrap_err();
+        assert_eq!("Line 3, column 10: Expected 1 pots, found 2", err.to_string());
+
+        let err = Garden::from_str("initial state: #..#\n\n..#.. -> #").unwrap_err();
+        assert_eq!("Line 3, column 11: Expected ` => `", err.to_string());
+    }
+
+    #[test]
+    fn test_unsettled() {
+        // Every pot becomes the xor of its neighbors, which keeps growing a Sierpinski triangle
+        let rules: Vec<String> = (0..32)
+            .map(|idx: usize| {
+                let pattern: String = (0..5).rev().map(|bit| if idx >> bit & 1 == 1 { '#' } else { '.' }).collect();
+                let plant = (idx >> 3 & 1) != (idx >> 1 & 1);
+
+                format!("{} => {}", pattern, if plant { '#' } else { '.' })
+            })
+            .collect();
+        let input = format!("initial state: #\n\n{}", rules.join("\n"));
+        let garden = Garden::from_str(&input).unwrap();
+
+        assert_eq!(garden.simulate(20).sum(), Part1::solve(&input).unwrap());
+        assert_eq!(garden.simulate(500).sum(), garden.sum_after(500).unwrap());
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(325, Part1::solve(EXAMPLE).unwrap());
+    }
+}
@@ -0,0 +1,360 @@
+use super::*;
+
+use std::fmt;
+use std::str::FromStr;
+use std::collections::HashSet;
+
+use grid::{Grid, Point};
+
+pub struct Part1<T>(::std::marker::PhantomData<T>);
+
+impl<T> Solve<T> for Part1<T>
+    where T: AsRef<str> {
+    type Output = Result<Point, Error>;
+
+    fn solve(input: T) -> <Self as Solve<T>>::Output {
+        let mut mine = Mine::from_str(input.as_ref())?;
+        let mut seen: HashSet<Vec<Cart>> = HashSet::new();
+
+        loop {
+            if mine.carts.len() < 2 {
+                return Err(From::from("Not enough carts left to crash"));
+            }
+
+            if !seen.insert(mine.state()) {
+                return Err(From::from("The carts never crash"));
+            }
+
+            if let Some(&crash) = mine.tick()?.first() {
+                return Ok(crash);
+            }
+        }
+    }
+}
+
+pub struct Part2<T>(::std::marker::PhantomData<T>);
+
+impl<T> Solve<T> for Part2<T>
+    where T: AsRef<str> {
+    type Output = Result<Point, Error>;
+
+    fn solve(input: T) -> <Self as Solve<T>>::Output {
+        let mut mine = Mine::from_str(input.as_ref())?;
+        let mut seen: HashSet<Vec<Cart>> = HashSet::new();
+
+        while mine.carts.len() > 1 {
+            if !seen.insert(mine.state()) {
+                return Err(From::from("The remaining carts never crash"));
+            }
+
+            mine.tick()?;
+        }
+
+        Ok(mine.carts.first().ok_or("Every cart crashed")?.position)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Track {
+    Empty,
+    Vertical,
+    Horizontal,
+    /// A `/` curve.
+    Slash,
+    /// A `\` curve.
+    Backslash,
+    Intersection,
+}
+
+impl fmt::Display for Track {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let c = match self {
+            Track::Empty => ' ',
+            Track::Vertical => '|',
+            Track::Horizontal => '-',
+            Track::Slash => '/',
+            Track::Backslash => '\\',
+            Track::Intersection => '+',
+        };
+
+        write!(f, "{}", c)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub fn left(self) -> Self {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        }
+    }
+
+    pub fn right(self) -> Self {
+        self.left().left().left()
+    }
+
+    fn step(self) -> Point {
+        match self {
+            Direction::Up => Point::new(0, -1),
+            Direction::Down => Point::new(0, 1),
+            Direction::Left => Point::new(-1, 0),
+            Direction::Right => Point::new(1, 0),
+        }
+    }
+
+    /// The direction after following a curve.
+    fn curve(self, track: Track) -> Self {
+        match (track, self) {
+            (Track::Slash, Direction::Up) | (Track::Slash, Direction::Down) => self.right(),
+            (Track::Slash, _) => self.left(),
+            (Track::Backslash, Direction::Up) | (Track::Backslash, Direction::Down) => self.left(),
+            (Track::Backslash, _) => self.right(),
+            _ => self,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cart {
+    pub position: Point,
+    pub direction: Direction,
+    /// The number of intersections passed so far, which picks the way to go at the next one.
+    pub intersections: usize,
+}
+
+impl fmt::Display for Cart {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let c = match self.direction {
+            Direction::Up => '^',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+            Direction::Right => '>',
+        };
+
+        write!(f, "{}", c)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mine {
+    pub tracks: Grid<Track>,
+    /// The carts that haven't crashed yet, in the order they move.
+    pub carts: Vec<Cart>,
+    /// Where carts crashed during the last tick.
+    pub crashes: Vec<Point>,
+}
+
+impl Mine {
+    /// Moves every cart one square in reading order, removing any that crash as soon as they
+    /// do. Returns where carts crashed, in the order they crashed, or an error if a cart runs
+    /// off the track.
+    pub fn tick(&mut self) -> Result<Vec<Point>, Error> {
+        self.carts.sort_by_key(|cart| cart.position);
+
+        let mut crashed = vec![false; self.carts.len()];
+        let mut crashes: Vec<Point> = Vec::new();
+
+        for idx in 0..self.carts.len() {
+            if crashed[idx] {
+                continue;
+            }
+
+            let cart = &mut self.carts[idx];
+            cart.position = cart.position + cart.direction.step();
+
+            match self.tracks.get(cart.position).cloned().unwrap_or(Track::Empty) {
+                Track::Empty => return Err(From::from(format!("Cart ran off the track at {}", cart.position))),
+                Track::Intersection => {
+                    cart.direction = match cart.intersections % 3 {
+                        0 => cart.direction.left(),
+                        1 => cart.direction,
+                        _ => cart.direction.right(),
+                    };
+                    cart.intersections += 1;
+                },
+                track => cart.direction = cart.direction.curve(track),
+            }
+
+            let position = cart.position;
+
+            let hit = (0..self.carts.len())
+                .find(|&other| other != idx && !crashed[other] && self.carts[other].position == position);
+
+            if let Some(other) = hit {
+                crashed[idx] = true;
+                crashed[other] = true;
+                crashes.push(position);
+            }
+        }
+
+        let mut crashed = crashed.into_iter();
+        self.carts.retain(|_cart| !crashed.next().unwrap_or(false));
+
+        self.crashes = crashes.clone();
+
+        Ok(crashes)
+    }
+
+    /// The carts in the order they move, with only the part of their intersection count that
+    /// affects where they go, so a repeated state means the carts are going round in circles.
+    fn state(&self) -> Vec<Cart> {
+        let mut carts: Vec<Cart> = self.carts.iter()
+            .map(|cart| Cart {
+                intersections: cart.intersections % 3,
+                ..*cart
+            })
+            .collect();
+
+        carts.sort_by_key(|cart| cart.position);
+        carts
+    }
+}
+
+impl FromStr for Mine {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        // Lines can lose their trailing spaces, so pad them all out to the same width
+        let width = s.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+        let padded: Vec<String> = s.lines()
+            .map(|line| format!("{:width$}", line, width = width))
+            .collect();
+
+        let tracks = Grid::parse(&padded.join("\n"), |c| match c {
+            ' ' => Ok(Track::Empty),
+            '|' | '^' | 'v' => Ok(Track::Vertical),
+            '-' | '<' | '>' => Ok(Track::Horizontal),
+            '/' => Ok(Track::Slash),
+            '\\' => Ok(Track::Backslash),
+            '+' => Ok(Track::Intersection),
+            _ => Err(From::from(format!("Invalid track {}", c))),
+        })?;
+
+        let carts: Vec<Cart> = Grid::<char>::from_str(&padded.join("\n"))?.iter()
+            .filter_map(|(position, c)| {
+                let direction = match c {
+                    '^' => Direction::Up,
+                    'v' => Direction::Down,
+                    '<' => Direction::Left,
+                    '>' => Direction::Right,
+                    _ => return None,
+                };
+
+                Some(Cart {
+                    position,
+                    direction,
+                    intersections: 0,
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            tracks,
+            carts,
+            crashes: Vec::new(),
+        })
+    }
+}
+
+/// Draws the tracks with the carts on them, and an `X` wherever carts crashed in the last tick.
+impl fmt::Display for Mine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let mut map: Grid<String> = self.tracks.map(|track| track.to_string());
+
+        for cart in self.carts.iter() {
+            map[cart.position] = cart.to_string();
+        }
+
+        for &crash in self.crashes.iter() {
+            map[crash] = "X".to_owned();
+        }
+
+        for row in map.rows() {
+            writeln!(f, "{}", row.concat().trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r"/->-\
+|   |  /----\
+| /-+--+-\  |
+| | |  | v  |
+\-+-/  \-+--/
+  \------/";
+
+    const EXAMPLE_2: &str = r"/>-<\
+|   |
+| /<+-\
+| | | v
+\>+</ |
+  |   ^
+  \<->/";
+
+    #[test]
+    fn test_tick() {
+        let mut mine = Mine::from_str(EXAMPLE).unwrap();
+
+        assert_eq!(EXAMPLE, mine.to_string().trim_end());
+
+        mine.tick().unwrap();
+        assert_eq!(r"/-->\
+|   |  /----\
+| /-+--+-\  |
+| | |  | |  |
+\-+-/  \->--/
+  \------/", mine.to_string().trim_end());
+
+        for _ in 0..12 {
+            assert!(mine.tick().unwrap().is_empty());
+        }
+
+        assert_eq!(vec![Point::new(7, 3)], mine.tick().unwrap());
+        assert_eq!(r"/---\
+|   |  /----\
+| /-+--+-\  |
+| | |  X |  |
+\-+-/  \-+--/
+  \------/", mine.to_string().trim_end());
+        assert!(mine.carts.is_empty());
+    }
+
+    #[test]
+    fn test_stuck_carts() {
+        let separate_loops = "/>\\ /<\\\n\\-/ \\-/";
+
+        assert_eq!("The carts never crash", Part1::solve(separate_loops).unwrap_err().to_string());
+        assert_eq!("The remaining carts never crash", Part2::solve(separate_loops).unwrap_err().to_string());
+
+        assert_eq!("Cart ran off the track at -1,0", Part1::solve("<-->").unwrap_err().to_string());
+        assert_eq!("Cart ran off the track at 4,1", Part2::solve("/->-<\\\n|  >\n\\---/").unwrap_err().to_string());
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(Point::new(7, 3), Part1::solve(EXAMPLE).unwrap());
+        assert!(Part1::solve("->-").is_err());
+        assert!(Part1::solve("-x-").is_err());
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(Point::new(6, 4), Part2::solve(EXAMPLE_2).unwrap());
+        assert!(Part2::solve("->-<-").is_err());
+    }
+}
@@ -0,0 +1,158 @@
+use super::*;
+
+pub struct Part1<T>(::std::marker::PhantomData<T>);
+
+impl<T> Solve<T> for Part1<T>
+    where T: AsRef<str> {
+    type Output = Result<String, Error>;
+
+    fn solve(input: T) -> <Self as Solve<T>>::Output {
+        let recipes: usize = input.as_ref().trim().parse()?;
+        let mut scoreboard = Scoreboard::new();
+
+        while scoreboard.len() < recipes + 10 {
+            scoreboard.step();
+        }
+
+        Ok(scoreboard.scores()[recipes..recipes + 10].iter()
+            .map(|score| score.to_string())
+            .collect())
+    }
+}
+
+pub struct Part2<T>(::std::marker::PhantomData<T>);
+
+impl<T> Solve<T> for Part2<T>
+    where T: AsRef<str> {
+    type Output = Result<usize, Error>;
+
+    fn solve(input: T) -> <Self as Solve<T>>::Output {
+        let pattern = read_digits(input.as_ref().trim())?;
+        let mut scoreboard = Scoreboard::new();
+
+        // A step can add two scores, so check every position the pattern could end at rather
+        // than just the end of the scoreboard
+        let mut end = pattern.len();
+
+        loop {
+            while end <= scoreboard.len() {
+                if scoreboard.scores()[end - pattern.len()..end] == pattern[..] {
+                    return Ok(end - pattern.len());
+                }
+
+                end += 1;
+            }
+
+            scoreboard.step();
+        }
+    }
+}
+
+fn read_digits(s: &str) -> Result<Vec<u8>, Error> {
+    if s.is_empty() {
+        return Err(From::from("Missing scores to look for"));
+    }
+
+    s.chars()
+        .map(|c| c.to_digit(10)
+            .map(|digit| digit as u8)
+            .ok_or_else(|| From::from(format!("Invalid score {}", c))))
+        .collect()
+}
+
+/// The recipe scores made so far, along with the recipe each of the two elves is on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scoreboard {
+    scores: Vec<u8>,
+    elves: [usize; 2],
+}
+
+impl Scoreboard {
+    pub fn new() -> Self {
+        Self {
+            scores: vec![3, 7],
+            elves: [0, 1],
+        }
+    }
+
+    pub fn scores(&self) -> &[u8] {
+        &self.scores
+    }
+
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    /// Makes the new recipes from the elves' current ones and moves them on, returning how many
+    /// scores were added.
+    pub fn step(&mut self) -> usize {
+        let sum = self.scores[self.elves[0]] + self.scores[self.elves[1]];
+
+        let added = if sum >= 10 {
+            self.scores.push(sum / 10);
+            self.scores.push(sum % 10);
+            2
+        } else {
+            self.scores.push(sum);
+            1
+        };
+
+        for elf in self.elves.iter_mut() {
+            *elf = (*elf + 1 + self.scores[*elf] as usize) % self.scores.len();
+        }
+
+        added
+    }
+}
+
+impl Default for Scoreboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scoreboard() {
+        let mut scoreboard = Scoreboard::default();
+
+        assert_eq!(2, scoreboard.step());
+        assert_eq!(2, scoreboard.step());
+        assert_eq!(1, scoreboard.step());
+        assert_eq!(&[3, 7, 1, 0, 1, 0, 1], scoreboard.scores());
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!("5158916779", Part1::solve("9").unwrap());
+        assert_eq!("0124515891", Part1::solve("5").unwrap());
+        assert_eq!("9251071085", Part1::solve("18").unwrap());
+        assert_eq!("5941429882", Part1::solve("2018\n").unwrap());
+        assert!(Part1::solve("-1").is_err());
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(9, Part2::solve("51589").unwrap());
+        assert_eq!(5, Part2::solve("01245").unwrap());
+        assert_eq!(18, Part2::solve("92510").unwrap());
+        assert_eq!(2018, Part2::solve("59414").unwrap());
+        assert!(Part2::solve("5x").is_err());
+        assert!(Part2::solve("").is_err());
+    }
+
+    #[test]
+    fn test_part2_mid_step() {
+        // "10" first appears as the two digits of a single step, and "0" as the second of them
+        assert_eq!(2, Part2::solve("10").unwrap());
+        assert_eq!(3, Part2::solve("0").unwrap());
+        assert_eq!(0, Part2::solve("37").unwrap());
+    }
+}
@@ -0,0 +1,458 @@
+use super::*;
+
+use std::fmt;
+use std::str::FromStr;
+use std::collections::HashSet;
+
+use grid::{Grid, Point};
+use search;
+
+pub struct Part1<T>(::std::marker::PhantomData<T>);
+
+impl<T> Solve<T> for Part1<T>
+    where T: AsRef<str> {
+    type Output = Result<Outcome, Error>;
+
+    fn solve(input: T) -> <Self as Solve<T>>::Output {
+        Cave::from_str(input.as_ref())?.fight()
+    }
+}
+
+pub struct Part2<T>(::std::marker::PhantomData<T>);
+
+impl<T> Solve<T> for Part2<T>
+    where T: AsRef<str> {
+    type Output = Result<Outcome, Error>;
+
+    fn solve(input: T) -> <Self as Solve<T>>::Output {
+        let cave = Cave::from_str(input.as_ref())?;
+
+        // At 200 every elf kills a goblin with a single hit, so there is no point going higher
+        for attack in 4..=START_HP {
+            let mut cave = cave.with_elf_attack(attack);
+
+            if let Some(outcome) = cave.fight_without_elf_deaths()? {
+                return Ok(outcome);
+            }
+        }
+
+        Err(From::from("The elves can't win without losses"))
+    }
+}
+
+const START_HP: i32 = 200;
+const START_ATTACK: i32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+    Elf,
+    Goblin,
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Kind::Elf => write!(f, "E"),
+            Kind::Goblin => write!(f, "G"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unit {
+    pub kind: Kind,
+    pub position: Point,
+    pub hp: i32,
+    pub attack: i32,
+}
+
+impl Unit {
+    pub fn is_alive(&self) -> bool {
+        self.hp > 0
+    }
+}
+
+/// How a battle ended: the number of full rounds fought and the hit points the winners had left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    pub winner: Kind,
+    pub rounds: usize,
+    pub hp: i32,
+}
+
+impl Outcome {
+    pub fn score(&self) -> i64 {
+        self.rounds as i64 * self.hp as i64
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.score())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cave {
+    /// `true` for the walls.
+    pub walls: Grid<bool>,
+    /// Every unit, including dead ones until the end of the round they died in.
+    pub units: Vec<Unit>,
+    /// The number of full rounds fought so far.
+    pub rounds: usize,
+}
+
+impl Cave {
+    pub fn with_elf_attack(&self, attack: i32) -> Self {
+        let mut cave = self.clone();
+
+        for unit in cave.units.iter_mut().filter(|unit| unit.kind == Kind::Elf) {
+            unit.attack = attack;
+        }
+
+        cave
+    }
+
+    /// The units still alive, in the order they take their turns.
+    pub fn alive(&self) -> impl Iterator<Item = &Unit> {
+        self.units.iter().filter(|unit| unit.is_alive())
+    }
+
+    /// Fights until one side is wiped out.
+    pub fn fight(&mut self) -> Result<Outcome, Error> {
+        loop {
+            if let Some(outcome) = self.round()? {
+                return Ok(outcome);
+            }
+        }
+    }
+
+    /// Fights until one side is wiped out, or gives up with `None` as soon as an elf dies.
+    pub fn fight_without_elf_deaths(&mut self) -> Result<Option<Outcome>, Error> {
+        let elves = self.count(Kind::Elf);
+
+        loop {
+            let outcome = self.round()?;
+
+            if self.count(Kind::Elf) < elves {
+                return Ok(None);
+            }
+
+            if outcome.is_some() {
+                return Ok(outcome);
+            }
+        }
+    }
+
+    /// Gives every unit a turn in reading order. Returns the outcome if a unit found no enemies
+    /// left during its turn, in which case the round doesn't count.
+    ///
+    /// Fails if no unit moved or attacked during the whole round, since every round after it
+    /// would go the same way and the battle would never end.
+    pub fn round(&mut self) -> Result<Option<Outcome>, Error> {
+        self.units.sort_by_key(|unit| unit.position);
+
+        let mut changed = false;
+
+        for idx in 0..self.units.len() {
+            if !self.units[idx].is_alive() {
+                continue;
+            }
+
+            let kind = self.units[idx].kind;
+
+            if self.alive().all(|unit| unit.kind == kind) {
+                self.units.retain(|unit| unit.is_alive());
+
+                return Ok(Some(Outcome {
+                    winner: kind,
+                    rounds: self.rounds,
+                    hp: self.alive().map(|unit| unit.hp).sum(),
+                }));
+            }
+
+            let moved = self.step(idx);
+            let attacked = self.attack(idx);
+
+            changed = changed || moved || attacked;
+        }
+
+        if !changed {
+            return Err(From::from(format!("Stalemate after {} rounds: no unit can reach an enemy", self.rounds)));
+        }
+
+        self.units.retain(|unit| unit.is_alive());
+        self.rounds += 1;
+
+        Ok(None)
+    }
+
+    fn count(&self, kind: Kind) -> usize {
+        self.alive().filter(|unit| unit.kind == kind).count()
+    }
+
+    /// Moves the unit a square towards the nearest square in range of an enemy, unless it's
+    /// already in range of one. Returns whether it moved.
+    fn step(&mut self, idx: usize) -> bool {
+        let unit = self.units[idx];
+
+        let enemies: HashSet<Point> = self.alive()
+            .filter(|other| other.kind != unit.kind)
+            .map(|other| other.position)
+            .collect();
+
+        let in_range = |point: &Point| point.neighbors4().iter().any(|p| enemies.contains(p));
+
+        if in_range(&unit.position) {
+            return false;
+        }
+
+        let occupied: HashSet<Point> = self.alive().map(|other| other.position).collect();
+        let walls = &self.walls;
+
+        let open = |point: &Point| -> Vec<Point> {
+            walls.neighbors4(*point)
+                .filter(|&p| !walls[p] && !occupied.contains(&p))
+                .collect()
+        };
+
+        match search::nearest(unit.position, open, in_range) {
+            Some((_target, path)) => {
+                self.units[idx].position = path[1];
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Hits the adjacent enemy with the fewest hit points, if there is one. Returns whether it
+    /// attacked.
+    fn attack(&mut self, idx: usize) -> bool {
+        let unit = self.units[idx];
+        let adjacent = unit.position.neighbors4();
+
+        let target = self.units.iter()
+            .enumerate()
+            .filter(|(_idx, other)| other.is_alive() && other.kind != unit.kind && adjacent.contains(&other.position))
+            .min_by_key(|(_idx, other)| (other.hp, other.position))
+            .map(|(idx, _other)| idx);
+
+        match target {
+            Some(target) => {
+                self.units[target].hp -= unit.attack;
+                true
+            },
+            None => false,
+        }
+    }
+}
+
+impl FromStr for Cave {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        let map = Grid::<char>::from_str(s)?;
+        let mut units: Vec<Unit> = Vec::new();
+
+        let walls = Grid::parse(s, |c| match c {
+            '#' => Ok(true),
+            '.' | 'E' | 'G' => Ok(false),
+            _ => Err(From::from(format!("Invalid square {}", c))),
+        })?;
+
+        for (position, &c) in map.iter() {
+            let kind = match c {
+                'E' => Kind::Elf,
+                'G' => Kind::Goblin,
+                _ => continue,
+            };
+
+            units.push(Unit {
+                kind,
+                position,
+                hp: START_HP,
+                attack: START_ATTACK,
+            });
+        }
+
+        if !units.iter().any(|unit| unit.kind == Kind::Elf) || !units.iter().any(|unit| unit.kind == Kind::Goblin) {
+            return Err(From::from("The cave needs both elves and goblins to fight"));
+        }
+
+        Ok(Self {
+            walls,
+            units,
+            rounds: 0,
+        })
+    }
+}
+
+/// Draws the cave the way the puzzle does, with the hit points of the units on each row after it.
+impl fmt::Display for Cave {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        for (y, row) in self.walls.rows().enumerate() {
+            let mut units: Vec<&Unit> = self.alive().filter(|unit| unit.position.y == y as isize).collect();
+            units.sort_by_key(|unit| unit.position);
+
+            let mut line: String = row.iter().map(|&wall| if wall { '#' } else { '.' }).collect();
+
+            for unit in units.iter() {
+                let x = unit.position.x as usize;
+                line.replace_range(x..=x, &unit.kind.to_string());
+            }
+
+            let hp: Vec<String> = units.iter().map(|unit| format!("{}({})", unit.kind, unit.hp)).collect();
+
+            if hp.is_empty() {
+                writeln!(f, "{}", line)?;
+            } else {
+                writeln!(f, "{}   {}", line, hp.join(", "))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_1: &str = "\
+#######
+#.G...#
+#...EG#
+#.#.#G#
+#..G#E#
+#.....#
+#######";
+
+    const EXAMPLE_2: &str = "\
+#######
+#G..#E#
+#E#E.E#
+#G.##.#
+#...#E#
+#...E.#
+#######";
+
+    const EXAMPLE_3: &str = "\
+#######
+#E..EG#
+#.#G.E#
+#E.##E#
+#G..#.#
+#..E#.#
+#######";
+
+    const EXAMPLE_4: &str = "\
+#######
+#E.G#.#
+#.#G..#
+#G.#.G#
+#G..#.#
+#...E.#
+#######";
+
+    const EXAMPLE_5: &str = "\
+#######
+#.E...#
+#.#..G#
+#.###.#
+#E#G#G#
+#...#G#
+#######";
+
+    const EXAMPLE_6: &str = "\
+#########
+#G......#
+#.E.#...#
+#..##..G#
+#...##..#
+#...#...#
+#.G...G.#
+#.....G.#
+#########";
+
+    #[test]
+    fn test_movement() {
+        let mut cave = Cave::from_str("\
+#########
+#G..G..G#
+#.......#
+#.......#
+#G..E..G#
+#.......#
+#.......#
+#G..G..G#
+#########").unwrap();
+
+        for _ in 0..3 {
+            cave.round().unwrap();
+        }
+
+        assert_eq!("\
+#########
+#.......#
+#..GGG..#   G(200), G(191), G(200)
+#..GEG..#   G(200), E(185), G(200)
+#G..G...#   G(200), G(200)
+#......G#   G(200)
+#.......#
+#.......#
+#########
+", cave.to_string());
+    }
+
+    #[test]
+    fn test_combat() {
+        let mut cave = Cave::from_str(EXAMPLE_1).unwrap();
+        let outcome = cave.fight().unwrap();
+
+        assert_eq!(Outcome { winner: Kind::Goblin, rounds: 47, hp: 590 }, outcome);
+        assert_eq!("\
+#######
+#G....#   G(200)
+#.G...#   G(131)
+#.#.#G#   G(59)
+#...#.#
+#....G#   G(200)
+#######
+", cave.to_string());
+    }
+
+    #[test]
+    fn test_invalid() {
+        let err = Part1::solve("#####\n#...#\n#####").unwrap_err();
+        assert_eq!("The cave needs both elves and goblins to fight", err.to_string());
+        assert!(Part1::solve("#####\n#E.E#\n#####").is_err());
+
+        let err = Part1::solve("#####\n#E#G#\n#####").unwrap_err();
+        assert_eq!("Stalemate after 0 rounds: no unit can reach an enemy", err.to_string());
+        assert!(Part2::solve("#####\n#E#G#\n#####").is_err());
+
+        // The elves kill the goblin they can reach, and then can't reach the other one
+        let err = Part1::solve("#######\n#EG.#G#\n#######").unwrap_err();
+        assert_eq!("Stalemate after 67 rounds: no unit can reach an enemy", err.to_string());
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(27730, Part1::solve(EXAMPLE_1).unwrap().score());
+        assert_eq!(36334, Part1::solve(EXAMPLE_2).unwrap().score());
+        assert_eq!(39514, Part1::solve(EXAMPLE_3).unwrap().score());
+        assert_eq!(27755, Part1::solve(EXAMPLE_4).unwrap().score());
+        assert_eq!(28944, Part1::solve(EXAMPLE_5).unwrap().score());
+        assert_eq!(18740, Part1::solve(EXAMPLE_6).unwrap().score());
+        assert!(Part1::solve("#.x#").is_err());
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(4988, Part2::solve(EXAMPLE_1).unwrap().score());
+        assert_eq!(31284, Part2::solve(EXAMPLE_3).unwrap().score());
+        assert_eq!(3478, Part2::solve(EXAMPLE_4).unwrap().score());
+        assert_eq!(6474, Part2::solve(EXAMPLE_5).unwrap().score());
+        assert_eq!(1140, Part2::solve(EXAMPLE_6).unwrap().score());
+    }
+}
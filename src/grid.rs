@@ -0,0 +1,371 @@
+use super::*;
+
+use std::fmt;
+use std::slice;
+use std::str::FromStr;
+use std::cmp::Ordering;
+use std::ops::{Add, Index, IndexMut, Sub};
+
+use parse::ParseError;
+
+/// A position on a grid, with `y` increasing downwards.
+///
+/// Points are ordered in reading order: top to bottom, then left to right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Point {
+    pub fn new(x: isize, y: isize) -> Self {
+        Self {
+            x,
+            y,
+        }
+    }
+
+    pub fn manhattan(&self, other: Point) -> usize {
+        ((self.x - other.x).abs() + (self.y - other.y).abs()) as usize
+    }
+
+    /// The four orthogonally adjacent points, in reading order.
+    pub fn neighbors4(&self) -> [Point; 4] {
+        [
+            Point::new(self.x, self.y - 1),
+            Point::new(self.x - 1, self.y),
+            Point::new(self.x + 1, self.y),
+            Point::new(self.x, self.y + 1),
+        ]
+    }
+
+    /// The eight adjacent points including diagonals, in reading order.
+    pub fn neighbors8(&self) -> [Point; 8] {
+        [
+            Point::new(self.x - 1, self.y - 1),
+            Point::new(self.x, self.y - 1),
+            Point::new(self.x + 1, self.y - 1),
+            Point::new(self.x - 1, self.y),
+            Point::new(self.x + 1, self.y),
+            Point::new(self.x - 1, self.y + 1),
+            Point::new(self.x, self.y + 1),
+            Point::new(self.x + 1, self.y + 1),
+        ]
+    }
+}
+
+impl Ord for Point {
+    fn cmp(&self, other: &Point) -> Ordering {
+        (self.y, self.x).cmp(&(other.y, other.x))
+    }
+}
+
+impl PartialOrd for Point {
+    fn partial_cmp(&self, other: &Point) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+/// A rectangular grid stored row by row in a single buffer.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T>
+    where T: Clone {
+
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn<F>(width: usize, height: usize, f: F) -> Self
+        where F: FnMut(Point) -> T {
+
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Point::new(x as isize, y as isize)))
+            .map(f)
+            .collect();
+
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parses a character map, one row per line, converting each character with `f`.
+    ///
+    /// All rows must be the same width. Errors give the line and column they occurred at.
+    pub fn parse<F>(s: &str, mut f: F) -> Result<Self, Error>
+        where F: FnMut(char) -> Result<T, Error> {
+
+        let mut width: Option<usize> = None;
+        let mut height: usize = 0;
+        let mut cells: Vec<T> = Vec::new();
+
+        for (row, line) in s.lines().enumerate() {
+            let mut line_width: usize = 0;
+
+            for (col, c) in line.chars().enumerate() {
+                let cell = f(c).map_err(|e| ParseError::at(row + 1, col + 1, e.to_string()))?;
+
+                cells.push(cell);
+                line_width += 1;
+            }
+
+            match width {
+                Some(width) if width != line_width => {
+                    return Err(From::from(ParseError::at_line(row + 1,
+                        format!("expected {} columns, found {}", width, line_width))));
+                },
+                _ => width = Some(line_width),
+            }
+
+            height += 1;
+        }
+
+        Ok(Self {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        point.x >= 0 && point.y >= 0 && (point.x as usize) < self.width && (point.y as usize) < self.height
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index_of(point).map(|idx| &self.cells[idx])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        match self.index_of(point) {
+            Some(idx) => Some(&mut self.cells[idx]),
+            None => None,
+        }
+    }
+
+    /// Every point on the grid, in reading order.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+
+        (0..self.height)
+            .flat_map(move |y| (0..width).map(move |x| Point::new(x as isize, y as isize)))
+    }
+
+    /// Every cell on the grid along with its point, in reading order.
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item = (Point, &'a T)> + 'a {
+        self.points().zip(self.cells.iter())
+    }
+
+    pub fn iter_mut<'a>(&'a mut self) -> impl Iterator<Item = (Point, &'a mut T)> + 'a {
+        self.points().zip(self.cells.iter_mut())
+    }
+
+    /// The orthogonal neighbors of `point` that lie on the grid, in reading order.
+    pub fn neighbors4<'a>(&'a self, point: Point) -> impl Iterator<Item = Point> + 'a {
+        point.neighbors4().to_vec().into_iter()
+            .filter(move |&p| self.contains(p))
+    }
+
+    /// The neighbors of `point` including diagonals that lie on the grid, in reading order.
+    pub fn neighbors8<'a>(&'a self, point: Point) -> impl Iterator<Item = Point> + 'a {
+        point.neighbors8().to_vec().into_iter()
+            .filter(move |&p| self.contains(p))
+    }
+
+    pub fn rows(&self) -> slice::Chunks<'_, T> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        if y < self.height {
+            Some(&self.cells[y * self.width..(y + 1) * self.width])
+        } else {
+            None
+        }
+    }
+
+    pub fn column<'a>(&'a self, x: usize) -> impl Iterator<Item = &'a T> + 'a {
+        let cells: &[T] = if x < self.width { &self.cells[x..] } else { &[] };
+
+        cells.iter().step_by(self.width.max(1))
+    }
+
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+        where F: FnMut(&T) -> U {
+
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    fn index_of(&self, point: Point) -> Option<usize> {
+        if self.contains(point) {
+            Some(point.y as usize * self.width + point.x as usize)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        match self.index_of(point) {
+            Some(idx) => &self.cells[idx],
+            None => panic!("Point {} out of bounds", point),
+        }
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        match self.index_of(point) {
+            Some(idx) => &mut self.cells[idx],
+            None => panic!("Point {} out of bounds", point),
+        }
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        Grid::parse(s, Ok)
+    }
+}
+
+impl<T> fmt::Display for Grid<T>
+    where T: fmt::Display {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        for row in self.rows() {
+            for item in row {
+                write!(f, "{}", item)?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_order() {
+        let mut points = vec![Point::new(2, 1), Point::new(0, 2), Point::new(1, 1), Point::new(5, 0)];
+        points.sort();
+
+        assert_eq!(vec![Point::new(5, 0), Point::new(1, 1), Point::new(2, 1), Point::new(0, 2)], points);
+
+        let neighbors = Point::new(3, 3).neighbors4();
+        let mut sorted = neighbors;
+        sorted.sort();
+
+        assert_eq!(sorted, neighbors);
+        assert_eq!(7, Point::new(1, -2).manhattan(Point::new(-3, 1)));
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let raw = "#.#\n..#\n";
+        let grid = Grid::from_str(raw).unwrap();
+
+        assert_eq!(3, grid.width());
+        assert_eq!(2, grid.height());
+        assert_eq!('#', grid[Point::new(2, 1)]);
+        assert_eq!(raw, grid.to_string());
+
+        let err = Grid::<char>::from_str("#.#\n..\n").unwrap_err();
+        assert_eq!("Line 2: expected 3 columns, found 2", err.to_string());
+
+        let err = Grid::parse("#.\n.x", |c| match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => Err(From::from(format!("Invalid cell {}", c))),
+        }).unwrap_err();
+        assert_eq!("Line 2, column 2: Invalid cell x", err.to_string());
+
+        assert_eq!(0, Grid::<char>::from_str("").unwrap().points().count());
+    }
+
+    #[test]
+    fn test_access() {
+        let mut grid = Grid::from_fn(4, 3, |p| p.x + 10 * p.y);
+
+        assert_eq!(Some(&21), grid.get(Point::new(1, 2)));
+        assert_eq!(None, grid.get(Point::new(4, 0)));
+        assert_eq!(None, grid.get(Point::new(0, -1)));
+
+        *grid.get_mut(Point::new(3, 2)).unwrap() = 99;
+        grid[Point::new(0, 0)] = -1;
+
+        assert_eq!(Some(&[20, 21, 22, 99][..]), grid.row(2));
+        assert_eq!(None, grid.row(3));
+        assert_eq!(vec![&1, &11, &21], grid.column(1).collect::<Vec<_>>());
+        assert_eq!(0, grid.column(4).count());
+        assert_eq!(3, grid.rows().count());
+        assert_eq!(Some((Point::new(1, 0), &1)), grid.iter().nth(1));
+        assert_eq!(Grid::new(4, 3, false), grid.map(|&v| v > 100));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::new(3, 3, ());
+
+        assert_eq!(vec![Point::new(1, 0), Point::new(0, 1)],
+                   grid.neighbors4(Point::new(0, 0)).collect::<Vec<_>>());
+        assert_eq!(4, grid.neighbors4(Point::new(1, 1)).count());
+        assert_eq!(8, grid.neighbors8(Point::new(1, 1)).count());
+        assert_eq!(vec![Point::new(1, 1), Point::new(2, 1), Point::new(1, 2)],
+                   grid.neighbors8(Point::new(2, 2)).collect::<Vec<_>>());
+    }
+}
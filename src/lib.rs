@@ -0,0 +1,111 @@
+#[macro_use]
+extern crate lazy_static;
+extern crate regex;
+extern crate chrono;
+#[cfg(feature = "parallel")]
+extern crate rayon;
+
+pub type Error = Box<dyn std::error::Error>;
+
+mod util;
+pub use util::*;
+
+mod constants;
+
+pub mod cycle;
+pub mod grid;
+pub mod parse;
+pub mod search;
+
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+
+pub fn run_all() {
+    let runners: Vec<(usize, usize)> = vec![
+        (1, 1),
+        (1, 2),
+        (2, 1),
+        (2, 2),
+        (3, 1),
+        (3, 2),
+        (4, 1),
+        (4, 2),
+        (5, 1),
+        (5, 2),
+        (6, 1),
+        (6, 2),
+        (7, 1),
+        (7, 2),
+        (8, 1),
+        (8, 2),
+        (9, 1),
+        (9, 2),
+        (10, 1),
+        (10, 2),
+        (11, 1),
+        (11, 2),
+        (12, 1),
+        (12, 2),
+        (13, 1),
+        (13, 2),
+        (14, 1),
+        (14, 2),
+        (15, 1),
+        (15, 2),
+    ];
+
+    for (day, part) in runners.iter() {
+        run(*day, *part);
+    }
+}
+
+pub fn run(day: usize, part: usize) {
+    let out: String = match (day, part) {
+        (1, 1) => day01::Part1::solve(&constants::day_1_input()).to_string(),
+        (1, 2) => day01::Part2::solve(&constants::day_1_input()).to_string(),
+        (2, 1) => day02::Part1::solve(constants::day_2_input()).to_string(),
+        (2, 2) => day02::Part2::solve(constants::day_2_input()).to_string(),
+        (3, 1) => day03::Part1::solve(constants::day_3_input()).unwrap().to_string(),
+        (3, 2) => day03::Part2::solve(constants::day_3_input()).unwrap().to_string(),
+        (4, 1) => day04::Part1::solve(constants::day_4_input()).unwrap().to_string(),
+        (4, 2) => day04::Part2::solve(constants::day_4_input()).unwrap().to_string(),
+        (5, 1) => day05::Part1::solve(constants::day_5_input()).unwrap().to_string(),
+        (5, 2) => day05::Part2::solve(constants::day_5_input()).unwrap().to_string(),
+        (6, 1) => day06::Part1::solve(constants::day_6_input()).unwrap().to_string(),
+        (6, 2) => day06::Part2::solve((constants::day_6_input(), 10000)).unwrap().to_string(),
+        (7, 1) => day07::Part1::solve(constants::day_7_input()).unwrap(),
+        (7, 2) => day07::Part2::solve((constants::day_7_input(), 5, 60)).unwrap().to_string(),
+        (8, 1) => day08::Part1::solve(constants::day_8_input()).unwrap().to_string(),
+        (8, 2) => day08::Part2::solve(constants::day_8_input()).unwrap().to_string(),
+        (9, 1) => day09::Part1::solve(constants::day_9_input()).unwrap().to_string(),
+        (9, 2) => day09::Part2::solve(constants::day_9_input()).unwrap().to_string(),
+        (10, 1) => day10::Part1::solve(constants::day_10_input()).unwrap(),
+        (10, 2) => day10::Part2::solve(constants::day_10_input()).unwrap().to_string(),
+        (11, 1) => day11::Part1::solve(constants::day_11_input()).unwrap().to_string(),
+        (11, 2) => day11::Part2::solve(constants::day_11_input()).unwrap().to_string(),
+        (12, 1) => day12::Part1::solve(constants::day_12_input()).unwrap().to_string(),
+        (12, 2) => day12::Part2::solve(constants::day_12_input()).unwrap().to_string(),
+        (13, 1) => day13::Part1::solve(constants::day_13_input()).unwrap().to_string(),
+        (13, 2) => day13::Part2::solve(constants::day_13_input()).unwrap().to_string(),
+        (14, 1) => day14::Part1::solve(constants::day_14_input()).unwrap(),
+        (14, 2) => day14::Part2::solve(constants::day_14_input()).unwrap().to_string(),
+        (15, 1) => day15::Part1::solve(constants::day_15_input()).unwrap().to_string(),
+        (15, 2) => day15::Part2::solve(constants::day_15_input()).unwrap().to_string(),
+        (_, _) => panic!("Day: {}, Part {} UNIMPLEMENTED", day, part),
+    };
+
+    println!("Day: {:02}, Part: {:02} => {}", day, part, out);
+}
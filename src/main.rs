@@ -1,59 +1,34 @@
-#[macro_use]
-extern crate lazy_static;
-extern crate itertools;
-extern crate regex;
-extern crate chrono;
+extern crate advent_of_code_2018;
 
-pub type Error = Box<std::error::Error>;
+use std::env;
+use std::process;
 
-mod util;
-pub use util::*;
-
-mod constants;
-
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
+use advent_of_code_2018::day05;
 
 fn main() {
-    run_all();
-}
+    let args: Vec<String> = env::args().skip(1).collect();
 
-pub fn run_all() {
-    let runners: Vec<(usize, usize)> = vec![
-        (1, 1),
-        (1, 2),
-        (2, 1),
-        (2, 2),
-        (3, 1),
-        (3, 2),
-        (4, 1),
-        (4, 2),
-        (5, 1),
-        (5, 2),
-    ];
-
-    for (day, part) in runners.iter() {
-        run(*day, *part);
+    match args.first().map(|arg| arg.as_str()) {
+        Some("trace") => trace(args.get(1)),
+        _ => advent_of_code_2018::run_all(),
     }
 }
 
-pub fn run(day: usize, part: usize) {
-    let out: String = match (day, part) {
-        (1, 1) => day01::Part1::solve(&constants::day_1_input()).to_string(),
-        (1, 2) => day01::Part2::solve(&constants::day_1_input()).to_string(),
-        (2, 1) => day02::Part1::solve(constants::day_2_input()).to_string(),
-        (2, 2) => day02::Part2::solve(constants::day_2_input()).to_string(),
-        (3, 1) => day03::Part1::solve(constants::day_3_input()).unwrap().to_string(),
-        (3, 2) => day03::Part2::solve(constants::day_3_input()).unwrap().to_string(),
-        (4, 1) => day04::Part1::solve(constants::day_4_input()).unwrap().to_string(),
-        (4, 2) => day04::Part2::solve(constants::day_4_input()).unwrap().to_string(),
-        (5, 1) => day05::Part1::solve(constants::day_5_input()).to_string(),
-        (5, 2) => day05::Part2::solve(constants::day_5_input()).to_string(),
-        (_, _) => panic!(format!("Day: {}, Part {} UNIMPLEMENTED", day, part)),
+/// Prints how a day 5 polymer collapses, one reaction at a time.
+fn trace(polymer: Option<&String>) {
+    let polymer = match polymer {
+        Some(polymer) => polymer,
+        None => {
+            eprintln!("Usage: trace <polymer>");
+            process::exit(1);
+        },
     };
 
-    println!("Day: {:02}, Part: {:02} => {}", day, part, out);
-}
\ No newline at end of file
+    match day05::parse_polymer(polymer) {
+        Ok(polymer) => print!("{}", day05::trace(polymer)),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        },
+    }
+}
@@ -0,0 +1,293 @@
+use super::*;
+
+use std::fmt;
+use std::str::FromStr;
+
+use regex::Regex;
+
+/// A parse failure, optionally located by line and column (both starting at 1).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new<S>(message: S) -> Self
+        where S: Into<String> {
+
+        Self {
+            line: None,
+            column: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn at(line: usize, column: usize, message: String) -> Self {
+        Self {
+            line: Some(line),
+            column: Some(column),
+            message,
+        }
+    }
+
+    pub fn at_column(column: usize, message: String) -> Self {
+        Self {
+            column: Some(column),
+            ..Self::new(message)
+        }
+    }
+
+    pub fn at_line(line: usize, message: String) -> Self {
+        Self {
+            line: Some(line),
+            ..Self::new(message)
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "Line {}, column {}: {}", line, column, self.message),
+            (Some(line), None) => write!(f, "Line {}: {}", line, self.message),
+            (None, Some(column)) => write!(f, "Column {}: {}", column, self.message),
+            (None, None) => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl ::std::error::Error for ParseError {}
+
+/// Parses every line with `f`, adding the line number to any error.
+pub fn lines<I, S, T, F>(lines: I, mut f: F) -> Result<Vec<T>, Error>
+    where I: IntoIterator<Item = S>, S: AsRef<str>, F: FnMut(&str) -> Result<T, Error> {
+
+    lines.into_iter()
+        .enumerate()
+        .map(|(idx, line)| f(line.as_ref()).map_err(|e| with_line(e, idx + 1)))
+        .collect()
+}
+
+/// Adds the line number to an error, turning it into a `ParseError` if it isn't one already.
+pub fn with_line(e: Error, line: usize) -> Error {
+    match e.downcast::<ParseError>() {
+        Ok(e) => Box::new(ParseError {
+            line: Some(line),
+            ..*e
+        }),
+        Err(e) => Box::new(ParseError::at_line(line, e.to_string())),
+    }
+}
+
+/// Extracts every integer in `s`, including negative ones, ignoring everything around them.
+pub fn ints<T>(s: &str) -> Result<Vec<T>, Error>
+    where T: FromStr, T::Err: fmt::Display {
+
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"-?\d+").unwrap();
+    }
+
+    RE.find_iter(s)
+        .map(|m| m.as_str().parse::<T>()
+            .map_err(|e| From::from(ParseError::at_column(m.start() + 1,
+                format!("Invalid integer `{}`: {}", m.as_str(), e)))))
+        .collect()
+}
+
+/// Parses `s` against a template where each `{}` stands for a value, returning the values as a
+/// tuple, e.g. `let (id, x, y): (u32, i64, i64) = template("#{} @ {},{}", s)?`.
+///
+/// Text outside the placeholders must match exactly. Each value extends up to the first
+/// occurrence of the text following its placeholder.
+pub fn template<T>(template: &str, s: &str) -> Result<T, Error>
+    where T: FromFields {
+
+    T::from_fields(&fields(template, s)?)
+}
+
+/// A value cut out of a line by `template`, along with the column it starts at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Field<'a> {
+    pub column: usize,
+    pub value: &'a str,
+}
+
+impl<'a> Field<'a> {
+    pub fn parse<T>(&self) -> Result<T, Error>
+        where T: FromStr, T::Err: fmt::Display {
+
+        self.value.parse::<T>()
+            .map_err(|e| From::from(ParseError::at_column(self.column,
+                format!("Invalid value `{}`: {}", self.value, e))))
+    }
+
+    /// Parses the value with `f`, moving any column in the error from the start of the value to
+    /// the start of the whole line. Errors without a column point at the start of the value.
+    pub fn parse_with<T, F>(&self, f: F) -> Result<T, Error>
+        where F: FnOnce(&str) -> Result<T, Error> {
+
+        f(self.value).map_err(|e| match e.downcast::<ParseError>() {
+            Ok(e) => Box::new(ParseError {
+                column: Some(self.column + e.column.map_or(0, |column| column - 1)),
+                ..*e
+            }) as Error,
+            Err(e) => Box::new(ParseError::at_column(self.column, e.to_string())),
+        })
+    }
+}
+
+/// Types that can be built from the fields matched by `template`.
+pub trait FromFields: Sized {
+    fn from_fields(fields: &[Field]) -> Result<Self, Error>;
+}
+
+macro_rules! tuple_from_fields {
+    ($count:expr; $($name:ident $idx:tt),+) => {
+        impl<$($name),+> FromFields for ($($name,)+)
+            where $($name: FromStr, <$name as FromStr>::Err: fmt::Display),+ {
+
+            fn from_fields(fields: &[Field]) -> Result<Self, Error> {
+                if fields.len() != $count {
+                    return Err(From::from(ParseError::new(format!(
+                        "Template has {} fields, expected {}", fields.len(), $count))));
+                }
+
+                Ok(($(fields[$idx].parse::<$name>()?,)+))
+            }
+        }
+    };
+}
+
+tuple_from_fields!(1; A 0);
+tuple_from_fields!(2; A 0, B 1);
+tuple_from_fields!(3; A 0, B 1, C 2);
+tuple_from_fields!(4; A 0, B 1, C 2, D 3);
+tuple_from_fields!(5; A 0, B 1, C 2, D 3, E 4);
+tuple_from_fields!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+
+/// Splits `s` into the values standing in for the placeholders of `template`, following the same
+/// rules as `template`.
+///
+/// Use this over `template` when a value needs parsing with something other than `FromStr`, so
+/// that `Field::parse_with` can report errors at the right column.
+pub fn fields<'a>(template: &str, s: &'a str) -> Result<Vec<Field<'a>>, Error> {
+    let literals: Vec<&str> = template.split("{}").collect();
+    let mut fields: Vec<Field<'a>> = Vec::with_capacity(literals.len() - 1);
+
+    let expected = |pos: usize, literal: &str| -> Error {
+        From::from(ParseError::at_column(pos + 1, format!("Expected `{}`", literal)))
+    };
+
+    if !s.starts_with(literals[0]) {
+        return Err(expected(0, literals[0]));
+    }
+
+    let mut pos = literals[0].len();
+
+    for (idx, literal) in literals.iter().enumerate().skip(1) {
+        let rest = &s[pos..];
+
+        let len = if idx == literals.len() - 1 {
+            // The last value runs up to the closing text at the end of the line
+            if !rest.ends_with(literal) {
+                return Err(expected(s.len(), literal));
+            }
+
+            rest.len() - literal.len()
+        } else if literal.is_empty() {
+            return Err(From::from(ParseError::new("Template has adjacent placeholders")));
+        } else {
+            rest.find(literal).ok_or_else(|| expected(s.len(), literal))?
+        };
+
+        if len == 0 {
+            return Err(From::from(ParseError::at_column(pos + 1, "Missing value".to_owned())));
+        }
+
+        fields.push(Field {
+            column: pos + 1,
+            value: &rest[..len],
+        });
+
+        pos += len + literal.len();
+    }
+
+    if pos != s.len() {
+        return Err(From::from(ParseError::at_column(pos + 1, "Unexpected trailing text".to_owned())));
+    }
+
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ints() {
+        assert_eq!(vec![9, 1, 0, -2], ints::<i64>("position=< 9,  1> velocity=< 0, -2>").unwrap());
+        assert_eq!(vec![1, 3, 4, 4, 4], ints::<usize>("#1 @ 3,4: 4x4").unwrap());
+        assert!(ints::<i64>("no numbers - here").unwrap().is_empty());
+
+        let err = ints::<u8>("1 300").unwrap_err();
+        assert_eq!("Column 3: Invalid integer `300`: number too large to fit in target type", err.to_string());
+    }
+
+    #[test]
+    fn test_template() {
+        let claim: (usize, usize, usize, usize, usize) = template("#{} @ {},{}: {}x{}", "#123 @ 3,2: 5x4").unwrap();
+        assert_eq!((123, 3, 2, 5, 4), claim);
+
+        let (time, event): (String, String) = template("[{}] {}", "[1518-11-01 00:00] Guard #10 begins shift").unwrap();
+        assert_eq!("1518-11-01 00:00", time);
+        assert_eq!("Guard #10 begins shift", event);
+
+        let (step,): (char,) = template("Step {} must be finished", "Step C must be finished").unwrap();
+        assert_eq!('C', step);
+
+        let err = template::<(i32, i32)>("{},{}", "3;4").unwrap_err();
+        assert_eq!("Column 4: Expected `,`", err.to_string());
+
+        let err = template::<(i32, i32)>("<{}, {}>", "<3, x>").unwrap_err();
+        assert_eq!("Column 5: Invalid value `x`: invalid digit found in string", err.to_string());
+
+        let err = template::<(i32,)>("#{} @", "# @").unwrap_err();
+        assert_eq!("Column 2: Missing value", err.to_string());
+
+        let err = template::<(i32,)>("#{}", "1").unwrap_err();
+        assert_eq!("Column 1: Expected `#`", err.to_string());
+
+        let err = template::<(i32,)>("{} x", "1 x y").unwrap_err();
+        assert_eq!("Column 6: Expected ` x`", err.to_string());
+
+        assert!(template::<(i32,)>("{}, {}", "1, 2").is_err());
+    }
+
+    #[test]
+    fn test_fields() {
+        let fields = fields("[{}] {}", "[12:30] Guard #x").unwrap();
+
+        assert_eq!(Field { column: 2, value: "12:30" }, fields[0]);
+        assert_eq!(Field { column: 9, value: "Guard #x" }, fields[1]);
+
+        let err = fields[1].parse_with(|s| template::<(u32,)>("Guard #{}", s)).unwrap_err();
+        assert_eq!("Column 16: Invalid value `x`: invalid digit found in string", err.to_string());
+
+        let err = fields[0].parse_with(|s| Ok(s.parse::<u32>()?)).unwrap_err();
+        assert_eq!("Column 2: invalid digit found in string", err.to_string());
+    }
+
+    #[test]
+    fn test_lines() {
+        let parsed: Vec<(i32, i32)> = lines("1,2\n3,4".lines(), |line| template("{},{}", line)).unwrap();
+        assert_eq!(vec![(1, 2), (3, 4)], parsed);
+
+        let err = lines(vec!["1,2", "3,4", "5;6"], |line| template::<(i32, i32)>("{},{}", line)).unwrap_err();
+        assert_eq!("Line 3, column 4: Expected `,`", err.to_string());
+
+        let err = lines(vec!["1", "x"], |line| Ok(line.parse::<i32>()?)).unwrap_err();
+        assert_eq!("Line 2: invalid digit found in string", err.to_string());
+    }
+}
@@ -0,0 +1,307 @@
+use std::hash::Hash;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+/// Shortest distances from a start node, and the paths that achieve them.
+#[derive(Debug, Clone)]
+pub struct Paths<N>
+    where N: Eq + Hash {
+
+    distances: HashMap<N, usize>,
+    parents: HashMap<N, N>,
+}
+
+impl<N> Paths<N>
+    where N: Eq + Hash + Clone {
+
+    fn new(start: N) -> Self {
+        let mut distances = HashMap::new();
+        distances.insert(start, 0);
+
+        Self {
+            distances,
+            parents: HashMap::new(),
+        }
+    }
+
+    pub fn distance(&self, node: &N) -> Option<usize> {
+        self.distances.get(node).cloned()
+    }
+
+    /// Every node reached, along with its distance.
+    pub fn distances(&self) -> &HashMap<N, usize> {
+        &self.distances
+    }
+
+    /// The path from the start to `node`, including both ends.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        if !self.distances.contains_key(node) {
+            return None;
+        }
+
+        let mut path = vec![node.clone()];
+
+        while let Some(parent) = self.parents.get(path.last()?) {
+            path.push(parent.clone());
+        }
+
+        path.reverse();
+
+        Some(path)
+    }
+}
+
+/// Breadth-first search over an unweighted graph, visiting every node reachable from `start`.
+///
+/// When several shortest paths reach a node, `path_to` returns the one through the neighbors
+/// `neighbors` yielded first.
+pub fn bfs<N, F, I>(start: N, mut neighbors: F) -> Paths<N>
+    where N: Eq + Hash + Clone, F: FnMut(&N) -> I, I: IntoIterator<Item = N> {
+
+    let mut paths = Paths::new(start.clone());
+    let mut queue: VecDeque<N> = VecDeque::new();
+    queue.push_back(start);
+
+    while let Some(node) = queue.pop_front() {
+        let distance = paths.distances[&node];
+
+        for next in neighbors(&node) {
+            if paths.distances.contains_key(&next) {
+                continue;
+            }
+
+            paths.distances.insert(next.clone(), distance + 1);
+            paths.parents.insert(next.clone(), node.clone());
+            queue.push_back(next);
+        }
+    }
+
+    paths
+}
+
+/// Finds the closest node matching `is_goal`, returning it with the path to reach it.
+///
+/// Ties between goals at the same distance go to the smallest goal. If `neighbors` yields nodes
+/// in ascending order, ties between paths to that goal go to the smallest first step, which with
+/// `grid::Point` gives the reading-order rules used on day 15.
+pub fn nearest<N, F, I, G>(start: N, mut neighbors: F, mut is_goal: G) -> Option<(N, Vec<N>)>
+    where N: Eq + Hash + Clone + Ord, F: FnMut(&N) -> I, I: IntoIterator<Item = N>, G: FnMut(&N) -> bool {
+
+    let mut paths = Paths::new(start.clone());
+    let mut layer: Vec<N> = vec![start];
+
+    while !layer.is_empty() {
+        if let Some(goal) = layer.iter().filter(|node| is_goal(node)).min() {
+            let path = paths.path_to(goal)?;
+
+            return Some((goal.clone(), path));
+        }
+
+        let mut next_layer: Vec<N> = Vec::new();
+
+        for node in layer.iter() {
+            let distance = paths.distances[node];
+
+            for next in neighbors(node) {
+                if paths.distances.contains_key(&next) {
+                    continue;
+                }
+
+                paths.distances.insert(next.clone(), distance + 1);
+                paths.parents.insert(next.clone(), node.clone());
+                next_layer.push(next);
+            }
+        }
+
+        layer = next_layer;
+    }
+
+    None
+}
+
+/// Dijkstra's algorithm over a graph with non-negative edge costs, visiting every node reachable
+/// from `start`.
+pub fn dijkstra<N, F, I>(start: N, neighbors: F) -> Paths<N>
+    where N: Eq + Hash + Clone, F: FnMut(&N) -> I, I: IntoIterator<Item = (N, usize)> {
+
+    best_first(start, neighbors, |_| 0, |_| false).0
+}
+
+/// A* search for the cheapest path from `start` to a node matching `is_goal`, returning its
+/// cost and the path.
+///
+/// `heuristic` must never overestimate the remaining cost; with `|_| 0` this is Dijkstra's
+/// algorithm stopping at the first goal.
+pub fn astar<N, F, I, H, G>(start: N, neighbors: F, heuristic: H, is_goal: G) -> Option<(usize, Vec<N>)>
+    where N: Eq + Hash + Clone, F: FnMut(&N) -> I, I: IntoIterator<Item = (N, usize)>,
+          H: FnMut(&N) -> usize, G: FnMut(&N) -> bool {
+
+    let (paths, goal) = best_first(start, neighbors, heuristic, is_goal);
+    let goal = goal?;
+
+    Some((paths.distance(&goal)?, paths.path_to(&goal)?))
+}
+
+/// Expands nodes cheapest estimate first until a goal is reached or the graph is exhausted.
+fn best_first<N, F, I, H, G>(start: N, mut neighbors: F, mut heuristic: H, mut is_goal: G) -> (Paths<N>, Option<N>)
+    where N: Eq + Hash + Clone, F: FnMut(&N) -> I, I: IntoIterator<Item = (N, usize)>,
+          H: FnMut(&N) -> usize, G: FnMut(&N) -> bool {
+
+    let mut paths = Paths::new(start.clone());
+
+    // The heap refers to nodes by their index in `pushed`, so nodes don't need to be `Ord`
+    let mut heap: BinaryHeap<Reverse<(usize, usize, usize)>> = BinaryHeap::new();
+    let mut pushed: Vec<N> = Vec::new();
+
+    heap.push(Reverse((heuristic(&start), 0, 0)));
+    pushed.push(start);
+
+    while let Some(Reverse((_estimate, cost, idx))) = heap.pop() {
+        let node = pushed[idx].clone();
+
+        // Skip stale entries for nodes that were since reached more cheaply
+        if cost > paths.distances[&node] {
+            continue;
+        }
+
+        if is_goal(&node) {
+            return (paths, Some(node));
+        }
+
+        for (next, step) in neighbors(&node) {
+            let next_cost = cost + step;
+
+            if paths.distances.get(&next).is_some_and(|&distance| distance <= next_cost) {
+                continue;
+            }
+
+            paths.distances.insert(next.clone(), next_cost);
+            paths.parents.insert(next.clone(), node.clone());
+
+            heap.push(Reverse((next_cost + heuristic(&next), next_cost, pushed.len())));
+            pushed.push(next);
+        }
+    }
+
+    (paths, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::str::FromStr;
+
+    use grid::{Grid, Point};
+
+    fn open_neighbors<'a>(grid: &'a Grid<char>) -> impl FnMut(&Point) -> Vec<Point> + 'a {
+        move |&point| grid.neighbors4(point)
+            .filter(|&p| grid[p] == '.')
+            .collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let grid = Grid::from_str("\
+#######
+#S..#.#
+#.#...#
+#.#.#.#
+#...#.#
+#######").unwrap();
+
+        let start = Point::new(1, 1);
+        let paths = bfs(start, open_neighbors(&grid));
+
+        assert_eq!(Some(0), paths.distance(&start));
+        assert_eq!(Some(6), paths.distance(&Point::new(5, 1)));
+        assert_eq!(Some(5), paths.distance(&Point::new(3, 4)));
+        assert_eq!(None, paths.distance(&Point::new(4, 1)));
+        assert_eq!(15, paths.distances().len());
+
+        assert_eq!(Some(vec![start, Point::new(2, 1), Point::new(3, 1), Point::new(3, 2), Point::new(3, 3)]),
+                   paths.path_to(&Point::new(3, 3)));
+        assert_eq!(None, paths.path_to(&Point::new(4, 1)));
+    }
+
+    #[test]
+    fn test_nearest_reading_order() {
+        let grid = Grid::from_str("\
+#######
+#.E...#
+#.....#
+#...G.#
+#######").unwrap();
+
+        let elf = Point::new(2, 1);
+        let goblin = Point::new(4, 3);
+        let in_range = |p: &Point| p.manhattan(goblin) == 1;
+
+        let (target, path) = nearest(elf, open_neighbors(&grid), in_range).unwrap();
+
+        assert_eq!(Point::new(4, 2), target);
+        assert_eq!(4, path.len());
+        assert_eq!(Point::new(3, 1), path[1]);
+
+        let grid = Grid::from_str("\
+#######
+#E..G.#
+#...#.#
+#.G.#G#
+#######").unwrap();
+
+        let goblins = [Point::new(4, 1), Point::new(2, 3), Point::new(5, 3)];
+        let in_range = |p: &Point| goblins.iter().any(|g| p.manhattan(*g) == 1);
+
+        let (target, path) = nearest(Point::new(1, 1), open_neighbors(&grid), in_range).unwrap();
+
+        assert_eq!(Point::new(3, 1), target);
+        assert_eq!(Point::new(2, 1), path[1]);
+
+        assert!(nearest(Point::new(1, 1), open_neighbors(&grid), |p| *p == Point::new(5, 1)).is_none());
+    }
+
+    #[test]
+    fn test_dijkstra_and_astar() {
+        let edges: HashMap<char, Vec<(char, usize)>> = vec![
+            ('a', vec![('b', 7), ('c', 9), ('f', 14)]),
+            ('b', vec![('a', 7), ('c', 10), ('d', 15)]),
+            ('c', vec![('a', 9), ('b', 10), ('d', 11), ('f', 2)]),
+            ('d', vec![('b', 15), ('c', 11), ('e', 6)]),
+            ('e', vec![('d', 6), ('f', 9)]),
+            ('f', vec![('a', 14), ('c', 2), ('e', 9)]),
+            ('g', vec![]),
+        ].into_iter().collect();
+
+        let neighbors = |node: &char| edges[node].clone();
+
+        let paths = dijkstra('a', neighbors);
+
+        assert_eq!(Some(20), paths.distance(&'e'));
+        assert_eq!(Some(11), paths.distance(&'f'));
+        assert_eq!(None, paths.distance(&'g'));
+        assert_eq!(Some(vec!['a', 'c', 'f', 'e']), paths.path_to(&'e'));
+
+        assert_eq!(Some((20, vec!['a', 'c', 'f', 'e'])), astar('a', neighbors, |_| 0, |&n| n == 'e'));
+        assert_eq!(None, astar('a', neighbors, |_| 0, |&n| n == 'g'));
+
+        let grid = Grid::from_str("\
+..........
+.########.
+.#......#.
+.#.####.#.
+...#....#.").unwrap();
+
+        let goal = Point::new(5, 4);
+        let weighted = |&point: &Point| grid.neighbors4(point)
+            .filter(|&p| grid[p] == '.')
+            .map(|p| (p, 1))
+            .collect::<Vec<_>>();
+
+        let (cost, path) = astar(Point::new(0, 0), weighted, |p| p.manhattan(goal), |&p| p == goal).unwrap();
+
+        assert_eq!(None, astar(Point::new(0, 0), weighted, |p| p.manhattan(goal), |&p| p == Point::new(4, 3)));
+        assert_eq!(cost + 1, path.len());
+        assert_eq!(17, cost);
+    }
+}